@@ -0,0 +1,63 @@
+//! Feeds arbitrary input through every parsing entry point, checking that none panic
+//! and that the iterators agree with each other.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uhttp_transfer_encoding::*;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(encs) = transfer_encodings_bytes(data) {
+        encs.for_each(drop);
+    }
+
+    let _ = TransferEncoding::from_bytes(data);
+
+    let s = match std::str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+
+    let fwd = transfer_encodings(s).collect::<Vec<_>>();
+    let mut back = transfer_encodings(s).rev().collect::<Vec<_>>();
+    back.reverse();
+    assert_eq!(fwd, back);
+    assert_eq!(fwd.len(), count_layers(s));
+    assert_eq!(transfer_encodings(s).len(), fwd.len());
+
+    for enc in &fwd {
+        assert!(enc.matches(&enc.to_string()));
+        let _ = TransferEncoding::parse_strict(enc.name());
+    }
+
+    assert!(transfer_encodings(&encode_header(fwd.iter().cloned())).eq(fwd.iter().cloned()));
+    assert_eq!(normalize(&normalize(s)), normalize(s));
+
+    transfer_encodings_strict(s).for_each(drop);
+    transfer_encodings_limited(s, 4).for_each(drop);
+    transfer_encodings_multi(s.split('\n')).for_each(drop);
+    transfer_encodings(s).std_only().for_each(drop);
+    transfer_encodings(s).other_only().for_each(drop);
+    assert_eq!(duplicate_encodings(s).any(|e| e.is_chunked()), has_duplicate_chunked(s));
+    effective_encodings(s).for_each(drop);
+
+    for (enc, raw) in transfer_encodings_spans(s) {
+        assert_eq!(enc, TransferEncoding::new(raw));
+    }
+
+    for (enc, range) in transfer_encodings_indexed(s) {
+        assert_eq!(enc, TransferEncoding::new(&s[range]));
+    }
+
+    let _ = validate_layers(s);
+    let _ = has_duplicate_chunked(s);
+
+    for coding in transfer_codings(s) {
+        coding.params().for_each(drop);
+        let _ = coding.quality();
+    }
+
+    for coding in te_codings(s) {
+        let _ = coding.quality();
+    }
+});
@@ -0,0 +1,286 @@
+//! Integration point for decoding message bodies according to their transfer encodings.
+
+use {transfer_encodings, TransferEncoding, TransferEncodingBuf};
+
+#[cfg(feature = "flate2")]
+use StdTransferEncoding;
+
+/// Decoder that reverses a single transfer encoding.
+///
+/// Decoders must be `Send` and `Sync` so a `Decoders` registry can be shared between
+/// threads.
+pub trait Decoder: Send + Sync {
+    /// Decode the given encoded body.
+    fn decode(&self, input: &[u8]) -> Result<Vec<u8>, DecodeError>;
+}
+
+impl<F> Decoder for F
+    where F: Fn(&[u8]) -> Result<Vec<u8>, DecodeError> + Send + Sync
+{
+    fn decode(&self, input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        self(input)
+    }
+}
+
+/// Errors that can occur when decoding a message body.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// No decoder is available for the contained encoding.
+    Unsupported(TransferEncodingBuf),
+    /// The body was malformed for its encoding.
+    Malformed,
+    /// The decoded body exceeded the size limit of its decoder.
+    TooLarge,
+    /// A decoder failed with the contained error.
+    Failed(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            DecodeError::Unsupported(ref enc) =>
+                write!(f, "unsupported transfer encoding {}", enc),
+            DecodeError::Malformed => f.write_str("malformed encoded body"),
+            DecodeError::TooLarge => f.write_str("decoded body too large"),
+            DecodeError::Failed(ref e) => write!(f, "decoding failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            DecodeError::Failed(ref e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+/// Registry of caller-supplied decoders, keyed by the encoding they reverse.
+#[derive(Default)]
+pub struct Decoders {
+    /// Registered decoders, searched in order.
+    decoders: Vec<(TransferEncodingBuf, Box<dyn Decoder>)>,
+}
+
+impl Decoders {
+    /// Create a new registry with no decoders.
+    pub fn new() -> Self {
+        Decoders::default()
+    }
+
+    /// Create a new registry with the built-in `chunked`, `gzip`, and `deflate` decoders,
+    /// each producing at most `limit` bytes.
+    #[cfg(feature = "flate2")]
+    pub fn builtin(limit: usize) -> Self {
+        let mut decoders = Decoders::new();
+
+        decoders.register(TransferEncoding::Std(StdTransferEncoding::Chunked),
+                          ChunkedDecoder::new(limit))
+                .register(TransferEncoding::Std(StdTransferEncoding::Gzip),
+                          GzipDecoder::new(limit))
+                .register(TransferEncoding::Std(StdTransferEncoding::Deflate),
+                          DeflateDecoder::new(limit));
+
+        decoders
+    }
+
+    /// Register the given decoder for the given encoding, replacing any decoder
+    /// previously registered for it.
+    pub fn register<D>(&mut self, enc: TransferEncoding, decoder: D) -> &mut Self
+        where D: Decoder + 'static
+    {
+        let enc = enc.to_owned();
+        self.decoders.retain(|(e, _)| *e != enc);
+        self.decoders.push((enc, Box::new(decoder)));
+        self
+    }
+
+    /// Retrieve the decoder registered for the given encoding, if any.
+    pub fn get(&self, enc: TransferEncoding) -> Option<&dyn Decoder> {
+        self.decoders.iter()
+            .find(|&(e, _)| e.as_encoding() == enc)
+            .map(|(_, d)| &**d)
+    }
+
+    /// Decode the given body according to the given `Transfer-Encoding` header value.
+    ///
+    /// Each encoding is reversed in the order yielded by `transfer_encodings`. The
+    /// `identity` encoding and empty elements are passed through unless a decoder is
+    /// registered for them, and any other encoding without a registered decoder results
+    /// in `DecodeError::Unsupported`.
+    pub fn decode(&self, header: &str, body: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        let mut body = body.to_vec();
+
+        for enc in transfer_encodings(header) {
+            body = match self.get(enc) {
+                Some(decoder) => decoder.decode(&body)?,
+                None if enc.is_identity() || enc == TransferEncoding::Other("") => body,
+                None => return Err(DecodeError::Unsupported(enc.to_owned())),
+            };
+        }
+
+        Ok(body)
+    }
+}
+
+/// Decode the given body according to the given `Transfer-Encoding` header value, using
+/// the built-in decoders.
+///
+/// Only `chunked`, `gzip`, and `deflate` are decoded, so any other encoding that requires
+/// a decoder results in `DecodeError::Unsupported`. Each layer may decode to at most
+/// `limit` bytes, guarding against bodies that decompress to many times their size. Use
+/// `Decoders::builtin` to add decoders for further encodings.
+#[cfg(feature = "flate2")]
+pub fn decode_all(s: &str, body: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+    Decoders::builtin(limit).decode(s, body)
+}
+
+/// Built-in decoder for the `chunked` encoding [RFC7230§4.1].
+///
+/// Chunk extensions and trailer fields are discarded.
+#[derive(Copy, Clone, Debug)]
+pub struct ChunkedDecoder {
+    /// Maximum size of the decoded body.
+    limit: usize,
+}
+
+impl ChunkedDecoder {
+    /// Create a new decoder producing at most `limit` bytes.
+    pub fn new(limit: usize) -> Self {
+        ChunkedDecoder { limit }
+    }
+}
+
+impl Decoder for ChunkedDecoder {
+    fn decode(&self, mut input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        let mut body = Vec::new();
+
+        loop {
+            let (line, rest) = split_line(input)?;
+            let size = chunk_size(line)?;
+
+            if size == 0 {
+                input = rest;
+                break;
+            }
+
+            if rest.len() < 2 || rest.len() - 2 < size || &rest[size..size + 2] != b"\r\n" {
+                return Err(DecodeError::Malformed);
+            }
+
+            if self.limit - body.len() < size {
+                return Err(DecodeError::TooLarge);
+            }
+
+            body.extend_from_slice(&rest[..size]);
+            input = &rest[size + 2..];
+        }
+
+        // Skip any trailer fields up to the terminating empty line.
+        loop {
+            let (line, rest) = split_line(input)?;
+            input = rest;
+
+            if line.is_empty() {
+                break;
+            }
+        }
+
+        if input.is_empty() {
+            Ok(body)
+        } else {
+            Err(DecodeError::Malformed)
+        }
+    }
+}
+
+/// Split the given input into the line before the first CRLF and the remainder after it.
+fn split_line(input: &[u8]) -> Result<(&[u8], &[u8]), DecodeError> {
+    match input.windows(2).position(|w| w == b"\r\n") {
+        Some(idx) => Ok((&input[..idx], &input[idx + 2..])),
+        None => Err(DecodeError::Malformed),
+    }
+}
+
+/// Parse the hexadecimal chunk size at the start of the given chunk header line.
+fn chunk_size(line: &[u8]) -> Result<usize, DecodeError> {
+    let end = line.iter().position(|&b| b == b';').unwrap_or(line.len());
+    // Chunk extensions may be preceded by whitespace.
+    let ws = line[..end].iter().rev().take_while(|&&b| b == b' ' || b == b'\t').count();
+    let hex = &line[..end - ws];
+
+    if hex.is_empty() {
+        return Err(DecodeError::Malformed);
+    }
+
+    hex.iter().try_fold(0usize, |size, &b| {
+        let digit = (b as char).to_digit(16).ok_or(DecodeError::Malformed)?;
+        size.checked_mul(16)
+            .and_then(|size| size.checked_add(digit as usize))
+            .ok_or(DecodeError::Malformed)
+    })
+}
+
+/// Built-in decoder for the `gzip` encoding.
+#[cfg(feature = "flate2")]
+#[derive(Copy, Clone, Debug)]
+pub struct GzipDecoder {
+    /// Maximum size of the decoded body.
+    limit: usize,
+}
+
+#[cfg(feature = "flate2")]
+impl GzipDecoder {
+    /// Create a new decoder producing at most `limit` bytes.
+    pub fn new(limit: usize) -> Self {
+        GzipDecoder { limit }
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl Decoder for GzipDecoder {
+    fn decode(&self, input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        read_all(::flate2::read::GzDecoder::new(input), self.limit)
+    }
+}
+
+/// Built-in decoder for the `deflate` encoding, which uses the zlib data format
+/// [RFC7230§4.2.2].
+#[cfg(feature = "flate2")]
+#[derive(Copy, Clone, Debug)]
+pub struct DeflateDecoder {
+    /// Maximum size of the decoded body.
+    limit: usize,
+}
+
+#[cfg(feature = "flate2")]
+impl DeflateDecoder {
+    /// Create a new decoder producing at most `limit` bytes.
+    pub fn new(limit: usize) -> Self {
+        DeflateDecoder { limit }
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl Decoder for DeflateDecoder {
+    fn decode(&self, input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        read_all(::flate2::read::ZlibDecoder::new(input), self.limit)
+    }
+}
+
+/// Read the given decompressor to the end, failing if it produces more than `limit`
+/// bytes.
+#[cfg(feature = "flate2")]
+fn read_all<R: std::io::Read>(r: R, limit: usize) -> Result<Vec<u8>, DecodeError> {
+    use std::io::Read;
+
+    let mut buf = Vec::new();
+
+    // Read one byte past the limit to detect bodies that exceed it.
+    match r.take((limit as u64).saturating_add(1)).read_to_end(&mut buf) {
+        Ok(_) if buf.len() > limit => Err(DecodeError::TooLarge),
+        Ok(_) => Ok(buf),
+        Err(e) => Err(DecodeError::Failed(Box::new(e))),
+    }
+}
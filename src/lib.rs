@@ -16,6 +16,42 @@
 //! assert_eq!(encs.next(), Some(TransferEncoding::Std(StdTransferEncoding::Gzip)));
 //! assert_eq!(encs.next(), None);
 //! ```
+//!
+//! ## Features
+//!
+//! The `std` feature is enabled by default. Disabling it allows the crate to be used in
+//! `no_std` environments.
+//!
+//! The optional `serde` feature implements `Serialize` and `Deserialize` for
+//! `StdTransferEncoding`, `TransferEncoding`, and `TransferEncodingBuf`, using their
+//! header tokens as the serialized form.
+//!
+//! The optional `flate2` feature provides built-in decoders for the `gzip` and `deflate`
+//! encodings, along with the `decode_all` convenience function. The `chunked` decoder is
+//! available with just the `std` feature.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(feature = "flate2")]
+extern crate flate2;
+
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+#[cfg(feature = "std")]
+mod decode;
+
+#[cfg(feature = "std")]
+pub use decode::{ChunkedDecoder, Decoder, DecodeError, Decoders};
+
+#[cfg(feature = "flate2")]
+pub use decode::{decode_all, DeflateDecoder, GzipDecoder};
 
 /// Create an iterator over transfer encoding layers from the given string in [the
 /// form](https://tools.ietf.org/html/rfc7230#section-3.3.1) used by the
@@ -23,94 +59,2057 @@
 ///
 /// Encodings are yielded in the order they must be decoded, with the outermost layer
 /// yielded first and the innermost layer yielded last.
-pub fn transfer_encodings<'a>(s: &'a str) -> impl Iterator<Item = TransferEncoding<'a>> {
-    s.split(',').rev().map(TransferEncoding::new)
+pub fn transfer_encodings<'a>(s: &'a str) -> TransferEncodings<'a> {
+    TransferEncodings {
+        iter: s.split(',').rev(),
+        remain: count_layers(s),
+    }
 }
 
-/// HTTP transfer encoding scheme.
+/// Create an iterator over the transfer encoding layers that effectively apply to a
+/// message with the given `Transfer-Encoding` header value.
+///
+/// This behaves like `transfer_encodings`, except that an empty or whitespace-only
+/// value, as well as a missing header represented by an empty string, yields a single
+/// `identity` encoding rather than an empty `Other` encoding.
+pub fn effective_encodings<'a>(s: &'a str) -> TransferEncodings<'a> {
+    if s.trim().is_empty() {
+        transfer_encodings(StdTransferEncoding::Identity.canonical_name())
+    } else {
+        transfer_encodings(s)
+    }
+}
+
+/// Count the number of transfer encoding layers in the given string, without parsing
+/// them.
+///
+/// This is the number of items yielded by `transfer_encodings`, so empty elements, such
+/// as those caused by stray commas, are included in the count.
+pub fn count_layers(s: &str) -> usize {
+    s.bytes().filter(|&b| b == b',').count() + 1
+}
+
+/// Create an iterator over transfer encoding layers from the values of multiple
+/// `Transfer-Encoding` header fields, given in the order they appear in the message.
+///
+/// The values are treated as if joined with commas into a single header value
+/// [RFC7230§3.2.2], and encodings are yielded in the same order as `transfer_encodings`.
+pub fn transfer_encodings_multi<'a, I>(lines: I) -> TransferEncodingsMulti<'a, I::IntoIter>
+    where I: IntoIterator<Item = &'a str>,
+          I::IntoIter: DoubleEndedIterator
+{
+    TransferEncodingsMulti {
+        lines: lines.into_iter().rev(),
+        cur: None,
+    }
+}
+
+/// Create an iterator over transfer encoding layers from the given string, rejecting
+/// invalid elements.
+///
+/// Encodings are yielded in the same order as `transfer_encodings`, but each element is
+/// parsed with `TransferEncoding::parse_strict`. In particular, empty elements caused
+/// by stray commas yield `ParseEncodingError::Empty` rather than `Other("")`.
+pub fn transfer_encodings_strict<'a>(s: &'a str) -> TransferEncodingsStrict<'a> {
+    TransferEncodingsStrict(s.split(',').rev())
+}
+
+/// Create an iterator over at most `max` transfer encoding layers from the given string.
+///
+/// Encodings are yielded in the same order as `transfer_encodings`. If the string
+/// contains more than `max` layers, `TransferEncodingError::TooManyLayers` is yielded
+/// after the first `max` layers, and iteration stops.
+pub fn transfer_encodings_limited<'a>(s: &'a str, max: usize) -> TransferEncodingsLimited<'a> {
+    TransferEncodingsLimited {
+        iter: transfer_encodings(s),
+        remain: Some(max),
+    }
+}
+
+/// Create an iterator over transfer encoding layers from the given string, along with
+/// the raw element each was parsed from.
+///
+/// Encodings are yielded in the same order as `transfer_encodings`. Each raw element is
+/// the untrimmed slice of `s` between commas, including its original casing,
+/// whitespace, and any parameters.
+pub fn transfer_encodings_spans<'a>(s: &'a str) -> TransferEncodingSpans<'a> {
+    TransferEncodingSpans(s.split(',').rev())
+}
+
+/// Create an iterator over transfer encoding layers from the given string, along with
+/// the byte range of each encoding's name within `s`.
+///
+/// Encodings are yielded in the same order as `transfer_encodings`. Each range covers
+/// only the name, without surrounding whitespace or parameters, so slicing `s` with it
+/// gives the name as written in the header.
+pub fn transfer_encodings_indexed<'a>(s: &'a str) -> TransferEncodingsIndexed<'a> {
+    TransferEncodingsIndexed {
+        src: s,
+        iter: transfer_encodings_spans(s),
+    }
+}
+
+/// Create an iterator over transfer encoding layers from the given string, in the order
+/// they were applied.
+///
+/// This is the reverse of `transfer_encodings`: encodings are yielded in the order they
+/// appear in the header, with the innermost layer (the first applied) yielded first and
+/// the outermost layer yielded last.
+pub fn transfer_encodings_applied<'a>(s: &'a str) -> core::iter::Rev<TransferEncodings<'a>> {
+    transfer_encodings(s).rev()
+}
+
+/// Create an iterator over transfer encoding layers from the given raw header bytes.
+///
+/// This behaves like `transfer_encodings`, but operates directly on the bytes of the
+/// header value. Only ASCII is accepted: if any byte is outside the ASCII range, the
+/// header is rejected with `ParseEncodingError::NonAscii` rather than being yielded
+/// as an `Other` encoding.
+pub fn transfer_encodings_bytes<'a>(b: &'a [u8])
+    -> Result<TransferEncodings<'a>, ParseEncodingError>
+{
+    ascii_str(b).map(transfer_encodings)
+}
+
+/// Create an iterator over transfer codings, including their parameters, from the given
+/// string in the form used by the `Transfer-Encoding` header field.
+///
+/// Codings are yielded in the same order as `transfer_encodings`. Note that the header is
+/// split on every comma, so parameter values can't contain commas, even when quoted.
+pub fn transfer_codings<'a>(s: &'a str) -> TransferCodings<'a> {
+    TransferCodings(s.split(',').rev())
+}
+
+/// Create an iterator over the codings accepted by a client, from the given string in
+/// [the form](https://tools.ietf.org/html/rfc7230#section-4.3) used by the `TE` header
+/// field.
+///
+/// Codings are yielded in the order they appear in the header. As with
+/// `transfer_codings`, parameter values can't contain commas.
+pub fn te_codings<'a>(s: &'a str) -> TeCodings<'a> {
+    TeCodings(s.split(','))
+}
+
+/// Collect the transfer encoding layers from the given string into a fixed-size array,
+/// without allocating.
+///
+/// Encodings are stored in the same order as `transfer_encodings`, and the number of
+/// layers stored is returned alongside the array. Any unused entries are filled with
+/// `Other("")`. If the string contains more than `N` layers,
+/// `TransferEncodingError::TooManyLayers` is returned.
+pub fn collect_codings<'a, const N: usize>(s: &'a str)
+    -> Result<([TransferEncoding<'a>; N], usize), TransferEncodingError>
+{
+    let encs = transfer_encodings(s);
+    let len = encs.len();
+
+    if len > N {
+        return Err(TransferEncodingError::TooManyLayers);
+    }
+
+    let mut buf = [TransferEncoding::Other(""); N];
+
+    for (slot, enc) in buf.iter_mut().zip(encs) {
+        *slot = enc;
+    }
+
+    Ok((buf, len))
+}
+
+/// Check that the layers in the given `Transfer-Encoding` header value are applied in a
+/// valid order.
+///
+/// If `chunked` is applied, it must be applied exactly once and must be the final
+/// encoding applied [RFC7230§3.3.1].
+pub fn validate_layers(s: &str) -> Result<(), TransferEncodingError> {
+    let mut count = 0;
+    let mut last = true;
+
+    // Empty elements from stray commas don't count as layers.
+    let encs = transfer_encodings(s).filter(|&e| e != TransferEncoding::Other(""));
+
+    for (idx, enc) in encs.enumerate() {
+        if enc == TransferEncoding::Std(StdTransferEncoding::Chunked) {
+            count += 1;
+            // The first encoding yielded is the last one applied.
+            last &= idx == 0;
+        }
+    }
+
+    if count > 1 {
+        Err(TransferEncodingError::DuplicateChunked)
+    } else if !last {
+        Err(TransferEncodingError::ChunkedNotLast)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check if `chunked` is the final encoding applied in the given `Transfer-Encoding`
+/// header value.
+///
+/// If so, the message is framed by the chunked encoding and any `Content-Length` must
+/// be ignored or the message rejected [RFC7230§3.3.3]. Empty elements are ignored
+/// [RFC7230§7].
+pub fn ends_with_chunked(s: &str) -> bool {
+    transfer_encodings(s)
+        .find(|&e| e != TransferEncoding::Other(""))
+        .is_some_and(|e| e.is_chunked())
+}
+
+/// Check if the `chunked` encoding appears more than once in the given
+/// `Transfer-Encoding` header value.
+pub fn has_duplicate_chunked(s: &str) -> bool {
+    transfer_encodings(s).filter(|e| e.is_chunked()).count() > 1
+}
+
+/// Create an iterator over encodings that appear more than once in the given
+/// `Transfer-Encoding` header value.
+///
+/// Each encoding is yielded once for every repeated occurrence after the first, in the
+/// same order as `transfer_encodings`. Nonstandard encodings are compared
+/// case-insensitively, and empty elements are ignored.
+///
+/// Standard encodings are checked in constant time, but each nonstandard encoding is
+/// compared against every element checked before it.
+pub fn duplicate_encodings<'a>(s: &'a str) -> DuplicateEncodings<'a> {
+    DuplicateEncodings {
+        src: s,
+        remain: Some(s),
+        seen: 0,
+    }
+}
+
+/// Serialize the given encodings into a `Transfer-Encoding` header value.
+///
+/// Encodings are taken in the order they must be decoded, as yielded by
+/// `transfer_encodings`, and written in the order they were applied. Nonstandard names
+/// are written as is, so the result parses back into the same sequence only if none of
+/// them contain a comma or semicolon, as is the case for names parsed by
+/// `transfer_encodings` or `TransferEncoding::parse_strict`.
+#[cfg(feature = "std")]
+pub fn encode_header<'a, I>(iter: I) -> String
+    where I: IntoIterator<Item = TransferEncoding<'a>>
+{
+    let encs = iter.into_iter().collect::<Vec<_>>();
+    let mut header = String::new();
+
+    for (idx, enc) in encs.iter().rev().enumerate() {
+        if idx > 0 {
+            header.push_str(", ");
+        }
+
+        header.push_str(enc.name());
+    }
+
+    header
+}
+
+/// Normalize the given `Transfer-Encoding` header value.
+///
+/// The header is parsed and written back in the order the encodings were applied, with
+/// names trimmed, empty elements and empty parameters removed, and elements separated
+/// by `", "`. Well-formed parameters are written as `;name=value`, with the value
+/// quoted only when it isn't a token. Coding and parameter names are case-insensitive
+/// [RFC7230§4], so they're ASCII-lowercased, while parameter values are left as is.
+/// Malformed parameters are kept as is, without surrounding whitespace. Header values
+/// that differ only in casing of names, whitespace, or quoting normalize to the same
+/// string.
+#[cfg(feature = "std")]
+pub fn normalize(s: &str) -> String {
+    let mut header = String::with_capacity(s.len());
+
+    for coding in transfer_codings(s).rev() {
+        if coding.encoding == TransferEncoding::Other("") && coding.params().next().is_none() {
+            continue;
+        }
+
+        if !header.is_empty() {
+            header.push_str(", ");
+        }
+
+        header.extend(coding.encoding.name().chars().map(|c| c.to_ascii_lowercase()));
+
+        let mut params = coding.params;
+
+        while let Some(param) = next_param(&mut params) {
+            if param.is_empty() {
+                continue;
+            }
+
+            header.push(';');
+
+            match parse_param(param) {
+                (name, Some(val)) if is_valid_param(param) => {
+                    header.push_str(&name.to_ascii_lowercase());
+                    header.push('=');
+
+                    if is_token(val) {
+                        header.push_str(val);
+                    } else {
+                        header.push('"');
+                        header.push_str(val);
+                        header.push('"');
+                    }
+                },
+                _ => header.push_str(param),
+            }
+        }
+    }
+
+    header
+}
+
+/// Interpret the given bytes as a string if they're all ASCII.
+fn ascii_str(b: &[u8]) -> Result<&str, ParseEncodingError> {
+    if !b.is_ascii() {
+        return Err(ParseEncodingError::NonAscii);
+    }
+
+    core::str::from_utf8(b).map_err(|_| ParseEncodingError::NonAscii)
+}
+
+/// Check if the given string is a nonempty sequence of `tchar` [RFC7230§3.2.6].
+fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(is_tchar)
+}
+
+/// Check if the given character is optional whitespace [RFC7230§3.2.3].
+fn is_ows(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+/// Check if the given byte is a `tchar` [RFC7230§3.2.6].
+fn is_tchar(b: u8) -> bool {
+    match b {
+        b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' | b'^' |
+        b'_' | b'`' | b'|' | b'~' => true,
+        _ => b.is_ascii_alphanumeric(),
+    }
+}
+
+/// Raw `Transfer-Encoding` header value, iterated as by `transfer_encodings`.
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
-pub enum TransferEncoding<'a> {
-    /// Standard defined scheme.
-    Std(StdTransferEncoding),
-    /// Unknown/nonstandard scheme with the contained name.
-    ///
-    /// The name is guaranteed to have no surrounding whitespace and requires
-    /// case-insensitive comparison to other strings.
-    Other(&'a str),
+pub struct TransferEncodingHeader<'a>(&'a str);
+
+impl<'a> TransferEncodingHeader<'a> {
+    /// Wrap the given header value.
+    pub fn new(s: &'a str) -> Self {
+        TransferEncodingHeader(s)
+    }
+
+    /// Retrieve the raw header value.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
 }
 
-impl<'a> TransferEncoding<'a> {
-    /// Parse a new `TransferEncoding` from the given string.
+impl<'a> IntoIterator for TransferEncodingHeader<'a> {
+    type Item = TransferEncoding<'a>;
+    type IntoIter = TransferEncodings<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        transfer_encodings(self.0)
+    }
+}
+
+/// Iterator over transfer encoding layers, created by `transfer_encodings`.
+#[derive(Clone)]
+pub struct TransferEncodings<'a> {
+    /// Remaining raw elements.
+    iter: core::iter::Rev<core::str::Split<'a, char>>,
+    /// Number of remaining elements.
+    remain: usize,
+}
+
+impl<'a> Iterator for TransferEncodings<'a> {
+    type Item = TransferEncoding<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.iter.next()?;
+        self.remain -= 1;
+        Some(TransferEncoding::new(next))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remain, Some(self.remain))
+    }
+}
+
+impl<'a> DoubleEndedIterator for TransferEncodings<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let next = self.iter.next_back()?;
+        self.remain -= 1;
+        Some(TransferEncoding::new(next))
+    }
+}
+
+impl<'a> ExactSizeIterator for TransferEncodings<'a> {}
+
+impl<'a> TransferEncodings<'a> {
+    /// Create an iterator over only the standard encodings, skipping all others.
+    pub fn std_only(self) -> StdOnly<'a> {
+        StdOnly(self)
+    }
+
+    /// Create an iterator over only the names of nonstandard encodings, skipping all
+    /// standard encodings.
+    pub fn other_only(self) -> OtherOnly<'a> {
+        OtherOnly(self)
+    }
+}
+
+/// Iterator over standard encodings, created by `TransferEncodings::std_only`.
+#[derive(Clone)]
+pub struct StdOnly<'a>(TransferEncodings<'a>);
+
+impl<'a> Iterator for StdOnly<'a> {
+    type Item = StdTransferEncoding;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.by_ref().filter_map(|e| e.std()).next()
+    }
+}
+
+impl<'a> DoubleEndedIterator for StdOnly<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.by_ref().rev().filter_map(|e| e.std()).next()
+    }
+}
+
+/// Iterator over nonstandard encoding names, created by `TransferEncodings::other_only`.
+#[derive(Clone)]
+pub struct OtherOnly<'a>(TransferEncodings<'a>);
+
+impl<'a> Iterator for OtherOnly<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.by_ref().filter_map(|e| e.other()).next()
+    }
+}
+
+impl<'a> DoubleEndedIterator for OtherOnly<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.by_ref().rev().filter_map(|e| e.other()).next()
+    }
+}
+
+/// Iterator over transfer encoding layers and their raw elements, created by
+/// `transfer_encodings_spans`.
+#[derive(Clone)]
+pub struct TransferEncodingSpans<'a>(core::iter::Rev<core::str::Split<'a, char>>);
+
+impl<'a> Iterator for TransferEncodingSpans<'a> {
+    type Item = (TransferEncoding<'a>, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|raw| (TransferEncoding::new(raw), raw))
+    }
+}
+
+impl<'a> DoubleEndedIterator for TransferEncodingSpans<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|raw| (TransferEncoding::new(raw), raw))
+    }
+}
+
+/// Iterator over transfer encoding layers and the byte ranges of their names, created by
+/// `transfer_encodings_indexed`.
+#[derive(Clone)]
+pub struct TransferEncodingsIndexed<'a> {
+    /// Header value being parsed.
+    src: &'a str,
+    /// Remaining layers.
+    iter: TransferEncodingSpans<'a>,
+}
+
+impl<'a> TransferEncodingsIndexed<'a> {
+    /// Compute the range of the given raw element's name within the header value.
+    fn range(&self, raw: &'a str) -> core::ops::Range<usize> {
+        let name = split_params(raw).0.trim();
+        let start = name.as_ptr() as usize - self.src.as_ptr() as usize;
+
+        start..start + name.len()
+    }
+}
+
+impl<'a> Iterator for TransferEncodingsIndexed<'a> {
+    type Item = (TransferEncoding<'a>, core::ops::Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (enc, raw) = self.iter.next()?;
+        Some((enc, self.range(raw)))
+    }
+}
+
+impl<'a> DoubleEndedIterator for TransferEncodingsIndexed<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (enc, raw) = self.iter.next_back()?;
+        Some((enc, self.range(raw)))
+    }
+}
+
+/// Iterator over a limited number of transfer encoding layers, created by
+/// `transfer_encodings_limited`.
+#[derive(Clone)]
+pub struct TransferEncodingsLimited<'a> {
+    /// Remaining layers.
+    iter: TransferEncodings<'a>,
+    /// Number of layers still allowed, or `None` if the limit was exceeded.
+    remain: Option<usize>,
+}
+
+impl<'a> Iterator for TransferEncodingsLimited<'a> {
+    type Item = Result<TransferEncoding<'a>, TransferEncodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remain = self.remain?;
+        let enc = self.iter.next()?;
+
+        if remain == 0 {
+            self.remain = None;
+            return Some(Err(TransferEncodingError::TooManyLayers));
+        }
+
+        self.remain = Some(remain - 1);
+        Some(Ok(enc))
+    }
+}
+
+/// Iterator over transfer encoding layers from multiple header fields, created by
+/// `transfer_encodings_multi`.
+#[derive(Clone)]
+pub struct TransferEncodingsMulti<'a, I> {
+    /// Remaining header values, from last to first.
+    lines: core::iter::Rev<I>,
+    /// Encodings remaining in the current header value.
+    cur: Option<TransferEncodings<'a>>,
+}
+
+impl<'a, I> Iterator for TransferEncodingsMulti<'a, I>
+    where I: DoubleEndedIterator<Item = &'a str>
+{
+    type Item = TransferEncoding<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(enc) = self.cur.as_mut().and_then(|c| c.next()) {
+                return Some(enc);
+            }
+
+            self.cur = Some(transfer_encodings(self.lines.next()?));
+        }
+    }
+}
+
+/// Iterator over strictly parsed transfer encoding layers, created by
+/// `transfer_encodings_strict`.
+#[derive(Clone)]
+pub struct TransferEncodingsStrict<'a>(core::iter::Rev<core::str::Split<'a, char>>);
+
+impl<'a> Iterator for TransferEncodingsStrict<'a> {
+    type Item = Result<TransferEncoding<'a>, ParseEncodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(TransferEncoding::parse_strict)
+    }
+}
+
+impl<'a> DoubleEndedIterator for TransferEncodingsStrict<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(TransferEncoding::parse_strict)
+    }
+}
+
+/// Iterator over transfer codings, created by `transfer_codings`.
+#[derive(Clone)]
+pub struct TransferCodings<'a>(core::iter::Rev<core::str::Split<'a, char>>);
+
+impl<'a> Iterator for TransferCodings<'a> {
+    type Item = TransferCoding<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(TransferCoding::new)
+    }
+}
+
+impl<'a> DoubleEndedIterator for TransferCodings<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(TransferCoding::new)
+    }
+}
+
+/// Iterator over `TE` codings, created by `te_codings`.
+#[derive(Clone)]
+pub struct TeCodings<'a>(core::str::Split<'a, char>);
+
+impl<'a> Iterator for TeCodings<'a> {
+    type Item = TeCoding<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(TeCoding::new)
+    }
+}
+
+impl<'a> DoubleEndedIterator for TeCodings<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(TeCoding::new)
+    }
+}
+
+/// Coding accepted by a client in the `TE` header field.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum TeCoding<'a> {
+    /// Client is willing to accept trailer fields in a chunked message.
+    Trailers,
+    /// Client is willing to accept the contained transfer coding.
+    Coding(TransferCoding<'a>),
+}
+
+impl<'a> TeCoding<'a> {
+    /// Parse a new `TeCoding` from the given string.
     pub fn new(s: &'a str) -> Self {
-        let s = s.trim();
+        let coding = TransferCoding::new(s);
 
-        match s.parse() {
-            Ok(enc) => TransferEncoding::Std(enc),
-            Err(_) => TransferEncoding::Other(s),
+        if coding.encoding.matches("trailers") {
+            TeCoding::Trailers
+        } else {
+            TeCoding::Coding(coding)
+        }
+    }
+
+    /// Retrieve the raw rank given by the `q` parameter, if any.
+    pub fn q(&self) -> Option<&'a str> {
+        match *self {
+            TeCoding::Trailers => None,
+            TeCoding::Coding(ref c) => c.param("q"),
+        }
+    }
+
+    /// Parse the rank given by the `q` parameter, if any, as with
+    /// `TransferCoding::quality`.
+    pub fn quality(&self) -> Result<Option<f32>, ParseEncodingError> {
+        match *self {
+            TeCoding::Trailers => Ok(None),
+            TeCoding::Coding(ref c) => c.quality(),
+        }
+    }
+}
+
+/// Iterator over repeated encodings, created by `duplicate_encodings`.
+pub struct DuplicateEncodings<'a> {
+    /// Header value being checked.
+    src: &'a str,
+    /// Leading part of the header value remaining to check, if any.
+    remain: Option<&'a str>,
+    /// Bitmask of the standard encodings seen so far.
+    seen: u8,
+}
+
+impl<'a> Iterator for DuplicateEncodings<'a> {
+    type Item = TransferEncoding<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = self.remain?;
+
+            let (elem, remain) = match rest.rfind(',') {
+                Some(idx) => (&rest[idx + 1..], Some(&rest[..idx])),
+                None => (rest, None),
+            };
+
+            self.remain = remain;
+
+            match TransferEncoding::new(elem) {
+                TransferEncoding::Std(enc) => {
+                    let bit = 1 << enc as u8;
+                    let dup = self.seen & bit != 0;
+                    self.seen |= bit;
+
+                    if dup {
+                        return Some(TransferEncoding::Std(enc));
+                    }
+                },
+                TransferEncoding::Other("") => {},
+                enc => {
+                    // Elements already checked follow this one in the header.
+                    let checked = &self.src[rest.len()..];
+
+                    if checked.split(',').any(|e| TransferEncoding::new(e) == enc) {
+                        return Some(enc);
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Split the given transfer coding into its name and the raw parameters following it.
+fn split_params(s: &str) -> (&str, &str) {
+    match s.find(';') {
+        Some(idx) => (&s[..idx], &s[idx + 1..]),
+        None => (s, ""),
+    }
+}
+
+/// HTTP transfer coding, consisting of an encoding scheme and its parameters.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct TransferCoding<'a> {
+    /// Encoding scheme.
+    pub encoding: TransferEncoding<'a>,
+    /// Raw parameters following the first semicolon.
+    params: &'a str,
+}
+
+impl<'a> TransferCoding<'a> {
+    /// Parse a new `TransferCoding` from the given string in the form `name *( ";" name
+    /// "=" value )`.
+    pub fn new(s: &'a str) -> Self {
+        let (name, params) = split_params(s);
+
+        TransferCoding {
+            encoding: TransferEncoding::new(name),
+            params,
+        }
+    }
+
+    /// Create an iterator over the `(name, value)` pairs of the coding's parameters.
+    pub fn params(&self) -> Params<'a> {
+        Params(self.params)
+    }
+
+    /// Retrieve the value of the first parameter with the given name, compared
+    /// case-insensitively.
+    ///
+    /// `None` is returned if no parameter has the name or the first one has no value.
+    pub fn param(&self, name: &str) -> Option<&'a str> {
+        self.params().find(|&(n, _)| n.eq_ignore_ascii_case(name)).and_then(|(_, v)| v)
+    }
+
+    /// Parse the quality value given by the `q` parameter, if any.
+    ///
+    /// The value must be in the range 0 to 1 with at most three decimal places
+    /// [RFC7231§5.3.1], otherwise `ParseEncodingError::InvalidQuality` is returned.
+    pub fn quality(&self) -> Result<Option<f32>, ParseEncodingError> {
+        match self.param("q") {
+            Some(q) => parse_qvalue(q).map(Some).ok_or(ParseEncodingError::InvalidQuality),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Iterator over transfer coding parameters, created by `TransferCoding::params`.
+///
+/// Each parameter name and value is yielded without surrounding whitespace, whether
+/// around the semicolons or the equals sign. If the value is a quoted string, the
+/// surrounding quotes are removed but any escapes within are left intact. A parameter
+/// without a value, which is malformed [RFC7230§4], is yielded with a value of `None`.
+#[derive(Copy, Clone, Debug)]
+pub struct Params<'a>(&'a str);
+
+impl<'a> Iterator for Params<'a> {
+    type Item = (&'a str, Option<&'a str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let param = next_param(&mut self.0)?;
+
+            // Skip empty parameters caused by stray semicolons.
+            if !param.is_empty() {
+                return Some(parse_param(param));
+            }
+        }
+    }
+}
+
+/// Split the first raw parameter, without surrounding whitespace, from the given raw
+/// parameters.
+fn next_param<'a>(s: &mut &'a str) -> Option<&'a str> {
+    let rest = *s;
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    let end = param_end(rest);
+    *s = if end < rest.len() { &rest[end + 1..] } else { "" };
+
+    Some(rest[..end].trim_matches(is_ows))
+}
+
+/// Split the given raw parameter into its name and its value, if any.
+fn parse_param(param: &str) -> (&str, Option<&str>) {
+    match param.find('=') {
+        // Tolerate whitespace around the equals sign [RFC7230§3.2.3].
+        Some(idx) => (param[..idx].trim_end_matches(is_ows),
+                      Some(unquote(param[idx + 1..].trim_start_matches(is_ows)))),
+        None => (param, None),
+    }
+}
+
+/// Check if the given raw parameters, following the first semicolon of a transfer
+/// coding, are all well formed [RFC7230§4].
+fn is_valid_params(mut s: &str) -> bool {
+    loop {
+        let end = param_end(s);
+
+        if !is_valid_param(s[..end].trim_matches(is_ows)) {
+            return false;
+        }
+
+        if end == s.len() {
+            return true;
+        }
+
+        s = &s[end + 1..];
+    }
+}
+
+/// Check if the given raw parameter, without surrounding whitespace, is well formed
+/// [RFC7230§4].
+fn is_valid_param(param: &str) -> bool {
+    let (name, val) = match param.find('=') {
+        Some(idx) => (&param[..idx], &param[idx + 1..]),
+        None => return false,
+    };
+
+    // Tolerate whitespace around the equals sign [RFC7230§3.2.3].
+    let val = val.trim_start_matches(is_ows);
+
+    is_token(name.trim_end_matches(is_ows)) && (is_token(val) || is_quoted_string(val))
+}
+
+/// Check if the given string is a valid quoted string [RFC7230§3.2.6].
+fn is_quoted_string(s: &str) -> bool {
+    let b = s.as_bytes();
+
+    if b.len() < 2 || b[0] != b'"' || b[b.len() - 1] != b'"' {
+        return false;
+    }
+
+    let mut escaped = false;
+
+    for &b in &b[1..b.len() - 1] {
+        let text = b == b'\t' || b == b' ' || (b >= 0x21 && b != 0x7f);
+
+        if escaped {
+            escaped = false;
+
+            if !text {
+                return false;
+            }
+        } else if b == b'\\' {
+            escaped = true;
+        } else if b == b'"' || !text {
+            return false;
+        }
+    }
+
+    !escaped
+}
+
+/// Find the index of the semicolon ending the first parameter in the given string,
+/// skipping over any quoted strings, or the length of the string if there is none.
+fn param_end(s: &str) -> usize {
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (idx, b) in s.bytes().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if quoted {
+            match b {
+                b'\\' => escaped = true,
+                b'"' => quoted = false,
+                _ => {},
+            }
+        } else {
+            match b {
+                b'"' => quoted = true,
+                b';' => return idx,
+                _ => {},
+            }
+        }
+    }
+
+    s.len()
+}
+
+/// Parse the given `qvalue` [RFC7231§5.3.1].
+fn parse_qvalue(s: &str) -> Option<f32> {
+    let (int, frac) = match s.find('.') {
+        Some(idx) => (&s[..idx], &s[idx + 1..]),
+        None => (s, ""),
+    };
+
+    if frac.len() > 3 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    // Parse the fraction as thousandths.
+    let thousandths = frac.bytes().chain(b"000".iter().cloned()).take(3)
+        .fold(0, |n, b| n * 10 + u32::from(b - b'0'));
+
+    match int {
+        "0" => Some(thousandths as f32 / 1000.0),
+        "1" if thousandths == 0 => Some(1.0),
+        _ => None,
+    }
+}
+
+/// Remove the surrounding quotes from the given value if it's a quoted string.
+fn unquote(s: &str) -> &str {
+    if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+        &s[1..s.len() - 1]
+    } else {
+        s
+    }
+}
+
+/// HTTP transfer encoding scheme.
+///
+/// Equality and hashing of `Other` names are case-insensitive.
+#[derive(Copy, Clone, Eq, Debug)]
+pub enum TransferEncoding<'a> {
+    /// Standard defined scheme.
+    Std(StdTransferEncoding),
+    /// Unknown/nonstandard scheme with the contained name.
+    ///
+    /// The name is guaranteed to have no surrounding whitespace and requires
+    /// case-insensitive comparison to other strings, such as with `matches`.
+    Other(&'a str),
+}
+
+impl<'a> TransferEncoding<'a> {
+    /// Parse a new `TransferEncoding` from the given string.
+    ///
+    /// Any parameters following the name are ignored.
+    pub fn new(s: &'a str) -> Self {
+        let s = split_params(s).0.trim();
+
+        match s.parse() {
+            Ok(enc) => TransferEncoding::Std(enc),
+            Err(_) => TransferEncoding::Other(s),
+        }
+    }
+
+    /// Parse a new `TransferEncoding` from the given string, rejecting invalid names.
+    ///
+    /// Unlike `new`, only spaces and tabs are trimmed from the name [RFC7230§3.2.3], and
+    /// the remaining name must be a valid
+    /// [token](https://tools.ietf.org/html/rfc7230#section-3.2.6), so names containing
+    /// other whitespace, control characters, or delimiters are rejected. Any parameters
+    /// following the name must each be a token name and a token or quoted-string value
+    /// separated by `=` [RFC7230§4].
+    pub fn parse_strict(s: &'a str) -> Result<Self, ParseEncodingError> {
+        let (name, params) = split_params(s);
+        let name = name.trim_matches(is_ows);
+
+        if name.is_empty() {
+            return Err(ParseEncodingError::Empty);
+        }
+
+        if !is_token(name) {
+            return Err(ParseEncodingError::InvalidToken);
+        }
+
+        if s.contains(';') && !is_valid_params(params) {
+            return Err(ParseEncodingError::InvalidParam);
+        }
+
+        match name.parse() {
+            Ok(enc) => Ok(TransferEncoding::Std(enc)),
+            Err(_) => Ok(TransferEncoding::Other(name)),
+        }
+    }
+
+    /// Retrieve the name of the encoding.
+    ///
+    /// This is the canonical name for standard encodings and the contained name for
+    /// nonstandard encodings.
+    pub fn name(&self) -> &'a str {
+        match *self {
+            TransferEncoding::Std(ref enc) => enc.canonical_name(),
+            TransferEncoding::Other(s) => s,
+        }
+    }
+
+    /// Check if the encoding has the given name, ignoring case and any surrounding
+    /// whitespace in the given name.
+    ///
+    /// Standard encodings are compared by their canonical name.
+    pub fn matches(&self, name: &str) -> bool {
+        self.name().eq_ignore_ascii_case(name.trim())
+    }
+
+    /// Check if the encoding is the standard `chunked` framing encoding.
+    pub fn is_chunked(&self) -> bool {
+        self.std().is_some_and(|enc| enc.is_chunked())
+    }
+
+    /// Check if the encoding is a standard compression encoding.
+    pub fn is_compression(&self) -> bool {
+        self.std().is_some_and(|enc| enc.is_compression())
+    }
+
+    /// Check if the encoding is the standard `identity` encoding.
+    pub fn is_identity(&self) -> bool {
+        self.std().is_some_and(|enc| enc.is_identity())
+    }
+
+    /// Check if the encoding can be decoded by a recipient that supports the given set of
+    /// standard encodings.
+    ///
+    /// Encodings that don't require a decoder are always supported, and nonstandard
+    /// encodings are never supported.
+    pub fn is_supported_by(&self, set: &[StdTransferEncoding]) -> bool {
+        self.std().is_some_and(|enc| !enc.requires_decoder() || set.contains(&enc))
+    }
+
+    /// Convert into an owned encoding that doesn't borrow from the source string.
+    ///
+    /// Note that this takes the encoding by value: called on a `&TransferEncoding`, such
+    /// as in `iter().map(|e| e.to_owned())`, `ToOwned::to_owned` is used instead and
+    /// yields a `TransferEncoding` copy. Dereference the encoding first or use
+    /// `TransferEncodingBuf::from` in that case.
+    #[cfg(feature = "std")]
+    pub fn to_owned(self) -> TransferEncodingBuf {
+        match self {
+            TransferEncoding::Std(enc) => TransferEncodingBuf::Std(enc),
+            TransferEncoding::Other(s) => TransferEncodingBuf::Other(s.to_string().into()),
+        }
+    }
+
+    /// Retrieve the standard encoding, if any.
+    fn std(&self) -> Option<StdTransferEncoding> {
+        match *self {
+            TransferEncoding::Std(enc) => Some(enc),
+            TransferEncoding::Other(_) => None,
+        }
+    }
+
+    /// Retrieve the nonstandard encoding name, if any.
+    fn other(&self) -> Option<&'a str> {
+        match *self {
+            TransferEncoding::Std(_) => None,
+            TransferEncoding::Other(s) => Some(s),
+        }
+    }
+
+    /// Parse a new `TransferEncoding` from the given raw bytes.
+    ///
+    /// Returns `ParseEncodingError::NonAscii` if any byte is outside the ASCII range.
+    pub fn from_bytes(b: &'a [u8]) -> Result<Self, ParseEncodingError> {
+        ascii_str(b).map(TransferEncoding::new)
+    }
+}
+
+impl<'a> core::fmt::Display for TransferEncoding<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl<'a> From<StdTransferEncoding> for TransferEncoding<'a> {
+    fn from(enc: StdTransferEncoding) -> Self {
+        TransferEncoding::Std(enc)
+    }
+}
+
+impl<'a> PartialEq for TransferEncoding<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        match (*self, *other) {
+            (TransferEncoding::Std(a), TransferEncoding::Std(b)) => a == b,
+            (TransferEncoding::Other(a), TransferEncoding::Other(b)) => a.eq_ignore_ascii_case(b),
+            _ => false,
+        }
+    }
+}
+
+impl<'a> core::hash::Hash for TransferEncoding<'a> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        match *self {
+            TransferEncoding::Std(ref enc) => {
+                state.write_u8(0);
+                enc.hash(state);
+            },
+            TransferEncoding::Other(s) => {
+                state.write_u8(1);
+
+                for b in s.bytes() {
+                    state.write_u8(b.to_ascii_lowercase());
+                }
+
+                // Terminate the name like `str` does, so adjacent fields can't collide.
+                state.write_u8(0xff);
+            },
+        }
+    }
+}
+
+/// Orders standard encodings before nonstandard encodings, with standard encodings
+/// ordered by canonical name and nonstandard encodings ordered case-insensitively by
+/// name.
+impl<'a> Ord for TransferEncoding<'a> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        match (*self, *other) {
+            (TransferEncoding::Std(a), TransferEncoding::Std(b)) => a.cmp(&b),
+            (TransferEncoding::Std(_), TransferEncoding::Other(_)) =>
+                core::cmp::Ordering::Less,
+            (TransferEncoding::Other(_), TransferEncoding::Std(_)) =>
+                core::cmp::Ordering::Greater,
+            (TransferEncoding::Other(a), TransferEncoding::Other(b)) => {
+                a.bytes().map(|b| b.to_ascii_lowercase())
+                    .cmp(b.bytes().map(|b| b.to_ascii_lowercase()))
+            },
+        }
+    }
+}
+
+impl<'a> PartialOrd for TransferEncoding<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares the encoding's name to the given string, as with `TransferEncoding::matches`.
+impl<'a> PartialEq<str> for TransferEncoding<'a> {
+    fn eq(&self, other: &str) -> bool {
+        self.matches(other)
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for TransferEncoding<'a> {
+    fn eq(&self, other: &&'b str) -> bool {
+        *self == **other
+    }
+}
+
+/// Owned HTTP transfer encoding scheme, created by `TransferEncoding::to_owned`.
+///
+/// Equality and hashing behave the same as `TransferEncoding`.
+#[cfg(feature = "std")]
+#[derive(Clone, Eq, Debug)]
+pub enum TransferEncodingBuf {
+    /// Standard defined scheme.
+    Std(StdTransferEncoding),
+    /// Unknown/nonstandard scheme with the contained name.
+    Other(std::borrow::Cow<'static, str>),
+}
+
+#[cfg(feature = "std")]
+impl TransferEncodingBuf {
+    /// Borrow as a `TransferEncoding`.
+    pub fn as_encoding<'a>(&'a self) -> TransferEncoding<'a> {
+        match *self {
+            TransferEncodingBuf::Std(enc) => TransferEncoding::Std(enc),
+            TransferEncodingBuf::Other(ref s) => TransferEncoding::Other(s),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> From<TransferEncoding<'a>> for TransferEncodingBuf {
+    fn from(enc: TransferEncoding<'a>) -> Self {
+        enc.to_owned()
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq for TransferEncodingBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_encoding() == other.as_encoding()
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::hash::Hash for TransferEncodingBuf {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_encoding().hash(state)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Ord for TransferEncodingBuf {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.as_encoding().cmp(&other.as_encoding())
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialOrd for TransferEncodingBuf {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for TransferEncodingBuf {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.as_encoding().fmt(f)
+    }
+}
+
+/// Incremental parser for a `Transfer-Encoding` header value that arrives in chunks.
+///
+/// Encodings are completed as each comma is crossed and are yielded in the order they
+/// appear in the header, as with `transfer_encodings_applied`. The partial encoding
+/// after the last comma is buffered until more bytes are fed or the parser is finished.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct IncrementalParser {
+    /// Partial encoding following the last comma.
+    partial: String,
+    /// Encodings completed by the last chunk.
+    complete: std::collections::VecDeque<TransferEncodingBuf>,
+}
+
+#[cfg(feature = "std")]
+impl IncrementalParser {
+    /// Create a new parser with no bytes fed.
+    pub fn new() -> Self {
+        IncrementalParser::default()
+    }
+
+    /// Feed the next chunk of the header value, and drain the encodings completed by it.
+    ///
+    /// If the chunk contains any bytes outside the ASCII range, it's ignored and
+    /// `ParseEncodingError::NonAscii` is returned.
+    pub fn feed(&mut self, bytes: &[u8])
+        -> Result<std::collections::vec_deque::Drain<'_, TransferEncodingBuf>,
+                  ParseEncodingError>
+    {
+        let mut elements = ascii_str(bytes)?.split(',');
+
+        // The first element always continues the partial encoding.
+        if let Some(first) = elements.next() {
+            self.partial.push_str(first);
+        }
+
+        for element in elements {
+            self.complete.push_back(TransferEncoding::new(&self.partial).to_owned());
+            self.partial.clear();
+            self.partial.push_str(element);
+        }
+
+        Ok(self.complete.drain(..))
+    }
+
+    /// Finish parsing, returning the final encoding following the last comma.
+    pub fn finish(self) -> TransferEncodingBuf {
+        TransferEncoding::new(&self.partial).to_owned()
+    }
+}
+
+/// Standard transfer encoding scheme, as defined by
+/// [IANA](http://www.iana.org/assignments/http-parameters/http-parameters.xhtml#transfer-coding).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum StdTransferEncoding {
+    /// Split into a series of chunks.
+    Chunked,
+    /// UNIX "compress" data format.
+    Compress,
+    /// Deflate compressed data format.
+    Deflate,
+    /// Gzip compressed data format.
+    Gzip,
+    /// No transformation.
+    Identity,
+    /// Brotli compressed data format.
+    Brotli,
+    /// Zstandard compressed data format.
+    Zstd,
+}
+
+impl StdTransferEncoding {
+    /// Retrieve every standard encoding.
+    pub const fn all() -> &'static [StdTransferEncoding] {
+        use self::StdTransferEncoding::*;
+
+        &[Chunked, Compress, Deflate, Gzip, Identity, Brotli, Zstd]
+    }
+
+    /// Check if the encoding is the `chunked` framing encoding.
+    pub const fn is_chunked(&self) -> bool {
+        matches!(*self, StdTransferEncoding::Chunked)
+    }
+
+    /// Check if the encoding is a compression encoding.
+    pub const fn is_compression(&self) -> bool {
+        use self::StdTransferEncoding::*;
+
+        match *self {
+            Compress | Deflate | Gzip | Brotli | Zstd => true,
+            Chunked | Identity => false,
+        }
+    }
+
+    /// Check if the encoding is the no-op `identity` encoding.
+    pub const fn is_identity(&self) -> bool {
+        matches!(*self, StdTransferEncoding::Identity)
+    }
+
+    /// Check if the encoding transforms the message body, so a decoder is needed to
+    /// recover the original body.
+    pub const fn requires_decoder(&self) -> bool {
+        !self.is_identity()
+    }
+
+    /// Retrieve the canonical lowercase name of the encoding, as registered with IANA.
+    ///
+    /// This can be used in `const` context, such as to build tables of names at compile
+    /// time, along with `all` and the classification methods. Parsing a name can't be
+    /// done in `const` context.
+    pub const fn canonical_name(&self) -> &'static str {
+        use self::StdTransferEncoding::*;
+
+        match *self {
+            Chunked => "chunked",
+            Compress => "compress",
+            Deflate => "deflate",
+            Gzip => "gzip",
+            Identity => "identity",
+            Brotli => "br",
+            Zstd => "zstd",
+        }
+    }
+
+    /// Check if the given string is a legacy alias of a standard encoding.
+    ///
+    /// The aliases `x-gzip` and `x-compress` are parsed as `Gzip` and `Compress`,
+    /// respectively, but this can be used to reject them when strictness is desired
+    /// [RFC7230§4.2.1, RFC7230§4.2.3].
+    pub fn is_legacy_alias(s: &str) -> bool {
+        let s = split_params(s).0.trim();
+        s.eq_ignore_ascii_case("x-gzip") || s.eq_ignore_ascii_case("x-compress")
+    }
+}
+
+/// Orders encodings by canonical name.
+impl Ord for StdTransferEncoding {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.canonical_name().cmp(other.canonical_name())
+    }
+}
+
+impl PartialOrd for StdTransferEncoding {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl core::str::FromStr for StdTransferEncoding {
+    type Err = ParseEncodingError;
+
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        use self::StdTransferEncoding::*;
+
+        if s.trim().is_empty() {
+            return Err(ParseEncodingError::Empty);
+        }
+
+        // Names are case-insensitive [RFC7230§4].
+        if s.eq_ignore_ascii_case("chunked") {
+            Ok(Chunked)
+        } else if s.eq_ignore_ascii_case("compress") ||
+                  s.eq_ignore_ascii_case("x-compress")
+        {
+            Ok(Compress)
+        } else if s.eq_ignore_ascii_case("deflate") {
+            Ok(Deflate)
+        } else if s.eq_ignore_ascii_case("gzip") || s.eq_ignore_ascii_case("x-gzip") {
+            Ok(Gzip)
+        } else if s.eq_ignore_ascii_case("identity") {
+            Ok(Identity)
+        } else if s.eq_ignore_ascii_case("br") {
+            Ok(Brotli)
+        } else if s.eq_ignore_ascii_case("zstd") {
+            Ok(Zstd)
+        } else {
+            Err(ParseEncodingError::Unknown)
+        }
+    }
+}
+
+impl core::fmt::Display for StdTransferEncoding {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.canonical_name())
+    }
+}
+
+/// Errors that can occur when parsing a transfer encoding.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum ParseEncodingError {
+    /// The encoding name was empty or contained only whitespace.
+    Empty,
+    /// The encoding name wasn't a recognized standard encoding.
+    Unknown,
+    /// The encoding contained bytes outside the ASCII range.
+    NonAscii,
+    /// The encoding name contained characters not allowed in a token.
+    InvalidToken,
+    /// The quality value was malformed or out of range.
+    InvalidQuality,
+    /// A transfer coding parameter was malformed.
+    InvalidParam,
+}
+
+impl core::fmt::Display for ParseEncodingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match *self {
+            ParseEncodingError::Empty => "empty transfer encoding",
+            ParseEncodingError::Unknown => "unknown transfer encoding",
+            ParseEncodingError::NonAscii => "non-ASCII transfer encoding",
+            ParseEncodingError::InvalidToken => "invalid transfer encoding token",
+            ParseEncodingError::InvalidQuality => "invalid transfer encoding quality value",
+            ParseEncodingError::InvalidParam => "invalid transfer encoding parameter",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseEncodingError {}
+
+/// Errors that can occur when validating a `Transfer-Encoding` header value.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum TransferEncodingError {
+    /// The `chunked` encoding was applied before another encoding.
+    ChunkedNotLast,
+    /// The `chunked` encoding was applied more than once.
+    DuplicateChunked,
+    /// More layers were given than allowed.
+    TooManyLayers,
+}
+
+impl core::fmt::Display for TransferEncodingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match *self {
+            TransferEncodingError::ChunkedNotLast => "chunked encoding not applied last",
+            TransferEncodingError::DuplicateChunked => "chunked encoding applied more than once",
+            TransferEncodingError::TooManyLayers => "too many transfer encoding layers",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TransferEncodingError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_te() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert_eq!(TransferEncoding::new("chunked"), Std(Chunked));
+        assert_eq!(TransferEncoding::new("chUNked"), Std(Chunked));
+        assert_eq!(TransferEncoding::new("chun  ked"), Other("chun  ked"));
+        assert_eq!(TransferEncoding::new("  chun\tked\t"), Other("chun\tked"));
+        assert_eq!(TransferEncoding::new("CHUNKED"), Std(Chunked));
+        assert_eq!(TransferEncoding::new("  CHUNKed "), Std(Chunked));
+        assert_eq!(TransferEncoding::new("compress"), Std(Compress));
+        assert_eq!(TransferEncoding::new(" cOMPress  "), Std(Compress));
+        assert_eq!(TransferEncoding::new("deflate"), Std(Deflate));
+        assert_eq!(TransferEncoding::new("\n\r dEflAte \r"), Std(Deflate));
+        assert_eq!(TransferEncoding::new("gzip"), Std(Gzip));
+        assert_eq!(TransferEncoding::new(" GZiP\r\r\t"), Std(Gzip));
+        assert_eq!(TransferEncoding::new("\tgzIP  "), Std(Gzip));
+        assert_eq!(TransferEncoding::new("identity"), Std(Identity));
+        assert_eq!(TransferEncoding::new("IDENTITY"), Std(Identity));
+        assert_eq!(TransferEncoding::new(" IdEntity\t"), Std(Identity));
+        assert_eq!(TransferEncoding::new("br"), Std(Brotli));
+        assert_eq!(TransferEncoding::new("BR"), Std(Brotli));
+        assert_eq!(TransferEncoding::new("  bR\r\n"), Std(Brotli));
+        assert_eq!(TransferEncoding::new("b r"), Other("b r"));
+        assert_eq!(TransferEncoding::new("zstd"), Std(Zstd));
+        assert_eq!(TransferEncoding::new("ZSTD"), Std(Zstd));
+        assert_eq!(TransferEncoding::new(" zstd "), Std(Zstd));
+        assert_eq!(TransferEncoding::new("\tzStD\r"), Std(Zstd));
+        assert_eq!(TransferEncoding::new("x-gzip"), Std(Gzip));
+        assert_eq!(TransferEncoding::new(" X-Gzip\t"), Std(Gzip));
+        assert_eq!(TransferEncoding::new("x-compress"), Std(Compress));
+        assert_eq!(TransferEncoding::new("\r X-COMPress  "), Std(Compress));
+        assert_eq!(TransferEncoding::new("x-deflate"), Other("x-deflate"));
+        assert_eq!(TransferEncoding::new(""), Other(""));
+        assert_eq!(TransferEncoding::new("    \t "), Other(""));
+        assert_eq!(TransferEncoding::new("ÆØБД❤"), Other("ÆØБД❤"));
+    }
+
+    #[test]
+    fn test_legacy_alias() {
+        assert!(StdTransferEncoding::is_legacy_alias("x-gzip"));
+        assert!(StdTransferEncoding::is_legacy_alias("  X-GZip\t"));
+        assert!(StdTransferEncoding::is_legacy_alias("x-compress"));
+        assert!(StdTransferEncoding::is_legacy_alias(" X-Compress "));
+        assert!(!StdTransferEncoding::is_legacy_alias("gzip"));
+        assert!(!StdTransferEncoding::is_legacy_alias("compress"));
+        assert!(!StdTransferEncoding::is_legacy_alias("x-deflate"));
+        assert!(!StdTransferEncoding::is_legacy_alias(""));
+        assert!(StdTransferEncoding::is_legacy_alias("x-gzip;q=1"));
+        assert!(StdTransferEncoding::is_legacy_alias(" X-Compress ; a=b"));
+        assert!(!StdTransferEncoding::is_legacy_alias("gzip;x-gzip"));
+    }
+
+    #[test]
+    fn test_canonical_name() {
+        use self::StdTransferEncoding::*;
+
+        fn check(s: &'static str) -> &'static str { s }
+
+        assert_eq!(check(Chunked.canonical_name()), "chunked");
+        assert_eq!(check(Compress.canonical_name()), "compress");
+        assert_eq!(check(Deflate.canonical_name()), "deflate");
+        assert_eq!(check(Gzip.canonical_name()), "gzip");
+        assert_eq!(check(Identity.canonical_name()), "identity");
+        assert_eq!(check(Brotli.canonical_name()), "br");
+        assert_eq!(check(Zstd.canonical_name()), "zstd");
+
+        assert!(Gzip.canonical_name() == "gzip");
+        assert!(Gzip.canonical_name() != "x-gzip");
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(StdTransferEncoding::all().len(), 7);
+
+        for &enc in StdTransferEncoding::all() {
+            assert_eq!(enc.canonical_name().parse(), Ok(enc));
+            assert_eq!(TransferEncoding::new(enc.canonical_name()), TransferEncoding::Std(enc));
+        }
+    }
+
+    #[test]
+    fn test_const() {
+        use self::StdTransferEncoding::*;
+
+        const ALLOWED: [&str; 3] = [Gzip.canonical_name(), Chunked.canonical_name(),
+                                    Brotli.canonical_name()];
+        const ALL: &[StdTransferEncoding] = StdTransferEncoding::all();
+        const CLASSES: [bool; 3] = [Chunked.is_chunked(), Gzip.is_compression(),
+                                    Identity.requires_decoder()];
+
+        assert_eq!(ALLOWED, ["gzip", "chunked", "br"]);
+        assert_eq!(ALL.len(), 7);
+        assert_eq!(CLASSES, [true, true, false]);
+
+        for enc in transfer_encodings("br, x-gzip, chunked").std_only() {
+            assert!(ALLOWED.contains(&enc.canonical_name()));
+        }
+
+        assert!(!ALLOWED.contains(&"deflate".parse::<StdTransferEncoding>().unwrap()
+                                            .canonical_name()));
+    }
+
+    #[test]
+    fn test_display_std() {
+        use self::StdTransferEncoding::*;
+
+        assert_eq!(Chunked.to_string(), "chunked");
+        assert_eq!(Compress.to_string(), "compress");
+        assert_eq!(Deflate.to_string(), "deflate");
+        assert_eq!(Gzip.to_string(), "gzip");
+        assert_eq!(Identity.to_string(), "identity");
+        assert_eq!(Brotli.to_string(), "br");
+        assert_eq!(Zstd.to_string(), "zstd");
+
+        for s in &["CHUNKED", "compress", "x-compress", "Deflate", "x-GZIP", "identity",
+                   "bR", "zStd"]
+        {
+            let enc = s.parse::<StdTransferEncoding>().unwrap();
+            assert_eq!(enc.to_string().parse(), Ok(enc));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert_eq!(Std(Gzip).to_string(), "gzip");
+        assert_eq!(Std(Brotli).to_string(), "br");
+        assert_eq!(Other("custom-enc").to_string(), "custom-enc");
+        assert_eq!(Other("CuStom").to_string(), "CuStom");
+
+        let encs = transfer_encodings(" X-GZip, custom-enc,CHUNKED ").collect::<Vec<_>>();
+        let fmt = encs.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(fmt, &["chunked", "custom-enc", "gzip"]);
+
+        for (enc, s) in encs.iter().zip(fmt.iter()) {
+            assert_eq!(TransferEncoding::new(s), *enc);
+        }
+    }
+
+    #[test]
+    fn test_parse_error() {
+        use self::StdTransferEncoding::*;
+
+        assert_eq!("gzip".parse::<StdTransferEncoding>(), Ok(Gzip));
+        assert_eq!("".parse::<StdTransferEncoding>(), Err(ParseEncodingError::Empty));
+        assert_eq!(" \t ".parse::<StdTransferEncoding>(), Err(ParseEncodingError::Empty));
+        assert_eq!("custom".parse::<StdTransferEncoding>(), Err(ParseEncodingError::Unknown));
+        assert_eq!("gz ip".parse::<StdTransferEncoding>(), Err(ParseEncodingError::Unknown));
+
+        match "".parse::<StdTransferEncoding>() {
+            Err(ParseEncodingError::Empty) => {},
+            _ => panic!(),
+        }
+
+        match "custom".parse::<StdTransferEncoding>() {
+            Err(ParseEncodingError::Unknown) => {},
+            _ => panic!(),
+        }
+
+        assert_eq!(ParseEncodingError::Empty.to_string(), "empty transfer encoding");
+        assert_eq!(ParseEncodingError::Unknown.to_string(), "unknown transfer encoding");
+    }
+
+    #[test]
+    fn test_eq_str() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert!(Std(Gzip) == "gzip");
+        assert!(Std(Gzip) == "GZip");
+        assert!(Std(Gzip) == " gzip\t");
+        assert!(Std(Brotli) == "BR");
+        assert!(Std(Gzip) != "x-gzip");
+        assert!(Std(Gzip) != "deflate");
+        assert!(Other("custom-enc") == "Custom-Enc");
+        assert!(Other("custom-enc") == "  CUSTOM-ENC ");
+        assert!(Other("custom-enc") != "custom");
+        assert!(Other("") == "  ");
+
+        assert_eq!(Std(Chunked), "chunked");
+        assert_eq!(Other("custom-enc"), "Custom-Enc");
+        assert_ne!(Other("custom-enc"), "gzip");
+        assert!(PartialEq::<str>::eq(&Std(Deflate), "DEFLATE"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_encode_header() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert_eq!(encode_header(vec![Std(Chunked), Other("custom"), Std(Gzip)]),
+                   "gzip, custom, chunked");
+        assert_eq!(encode_header(vec![Std(Chunked)]), "chunked");
+        assert_eq!(encode_header(vec![]), "");
+        assert_eq!(encode_header(vec![Other(""), Other("")]), ", ");
+        assert_eq!(transfer_encodings(&encode_header(transfer_encodings(","))).len(), 2);
+
+        let s = " X-GZip,Custom-Enc ,  BR, CHUNKED";
+        let header = encode_header(transfer_encodings(s));
+        assert_eq!(header, "gzip, Custom-Enc, br, chunked");
+        assert!(transfer_encodings(&header).eq(transfer_encodings(s)));
+
+        assert_eq!(encode_header(vec![Other("a,b")]), "a,b");
+        assert_eq!(transfer_encodings("a,b").len(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(" GZip ,  chunked"), "gzip, chunked");
+        assert_eq!(normalize("gzip, chunked"), "gzip, chunked");
+        assert_eq!(normalize("\tX-GZIP,DEFLATE\r,Chunked  "), "gzip, deflate, chunked");
+        assert_eq!(normalize("  Custom-Enc ;Q = 1, BR "), "custom-enc;q=1, br");
+        assert_eq!(normalize("gzip,, ,chunked,"), "gzip, chunked");
+        assert_eq!(normalize(""), "");
+        assert_eq!(normalize(" ÆØ "), "ÆØ");
+        assert_eq!(normalize("custom; a=\"b\"; C=\"x y\" ;; d=\"\", chunked"),
+                   "custom;a=b;c=\"x y\";d=\"\", chunked");
+        assert_eq!(normalize("custom; a=\"x\\\";y\"; Key = Val"),
+                   "custom;a=\"x\\\";y\";key=Val");
+        assert_eq!(normalize("custom; Flag ; a=x\"y"), "custom;Flag;a=x\"y");
+        assert_eq!(normalize("custom; b\"=1; c=2"), "custom;b\"=1; c=2");
+        assert_eq!(normalize("custom; a="), "custom;a=");
+        assert_eq!(normalize(" ;a=1, gzip"), ";a=1, gzip");
+        assert_ne!(normalize("custom; a=x\"y"), normalize("custom"));
+    }
+
+    #[test]
+    fn test_from_std() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert_eq!(TransferEncoding::from(Gzip), Std(Gzip));
+
+        let enc: TransferEncoding = Chunked.into();
+        assert_eq!(enc, Std(Chunked));
+
+        let encs: Vec<TransferEncoding> = vec![Gzip.into(), Other("custom"), Chunked.into()];
+        assert_eq!(encs, &[Std(Gzip), Other("custom"), Std(Chunked)]);
+    }
+
+    #[test]
+    fn test_classify() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert!(Std(Chunked).is_chunked());
+        assert!(!Std(Chunked).is_compression());
+        assert!(!Std(Chunked).is_identity());
+
+        for &enc in &[Compress, Deflate, Gzip, Brotli, Zstd] {
+            assert!(!enc.is_chunked());
+            assert!(enc.is_compression());
+            assert!(!enc.is_identity());
+            assert!(Std(enc).is_compression());
+        }
+
+        assert!(!Std(Identity).is_chunked());
+        assert!(!Std(Identity).is_compression());
+        assert!(Std(Identity).is_identity());
+
+        assert!(!Other("chunked-ext").is_chunked());
+        assert!(!Other("custom").is_compression());
+        assert!(!Other("").is_identity());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_owned() {
+        use std::borrow::Cow;
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let header = String::from("gzip, Custom-Enc, chunked");
+        let owned = transfer_encodings(&header).map(|e| e.to_owned()).collect::<Vec<_>>();
+        drop(header);
+
+        assert_eq!(owned.len(), 3);
+        assert_eq!(owned[0], TransferEncodingBuf::Std(Chunked));
+        assert_eq!(owned[1], TransferEncodingBuf::Other(Cow::Borrowed("custom-enc")));
+        assert_eq!(owned[2], TransferEncodingBuf::Std(Gzip));
+        assert_eq!(owned[1].as_encoding(), Other("custom-enc"));
+        assert_eq!(owned[1].to_string(), "Custom-Enc");
+        assert_eq!(TransferEncodingBuf::from(Std(Zstd)), TransferEncodingBuf::Std(Zstd));
+
+        let encs = transfer_encodings("gzip, Custom-Enc").collect::<Vec<_>>();
+        // Through a reference, `ToOwned::to_owned` takes precedence.
+        let copies: Vec<TransferEncoding> = encs.iter().map(|e| e.to_owned()).collect();
+        assert_eq!(copies, encs);
+        let owned = encs.iter().map(|&e| TransferEncodingBuf::from(e)).collect::<Vec<_>>();
+        assert_eq!(owned, &[TransferEncodingBuf::Other("custom-enc".into()),
+                            TransferEncodingBuf::Std(Gzip)]);
+        assert_eq!(encs.iter().map(|e| (*e).to_owned()).collect::<Vec<_>>(), owned);
+    }
+
+    #[test]
+    fn test_supported() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert!(Chunked.requires_decoder());
+        assert!(Gzip.requires_decoder());
+        assert!(Compress.requires_decoder());
+        assert!(!Identity.requires_decoder());
+
+        let set = [Gzip, Chunked];
+        assert!(Std(Gzip).is_supported_by(&set));
+        assert!(Std(Chunked).is_supported_by(&set));
+        assert!(Std(Identity).is_supported_by(&set));
+        assert!(!Std(Compress).is_supported_by(&set));
+        assert!(!Std(Deflate).is_supported_by(&set));
+        assert!(!Other("gzip-ext").is_supported_by(&set));
+        assert!(!Std(Gzip).is_supported_by(&[]));
+
+        assert!(transfer_encodings("gzip, chunked").all(|e| e.is_supported_by(&set)));
+        assert!(!transfer_encodings("compress, chunked").all(|e| e.is_supported_by(&set)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_decoders() {
+        use std::sync::{Arc, Mutex};
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        /// Mock decoder that strips a tag byte and records that it ran.
+        struct Strip(u8, Arc<Mutex<Vec<u8>>>);
+
+        impl Decoder for Strip {
+            fn decode(&self, input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+                self.1.lock().unwrap().push(self.0);
+
+                match input.split_first() {
+                    Some((&b, rest)) if b == self.0 => Ok(rest.to_vec()),
+                    _ => Err(DecodeError::Malformed),
+                }
+            }
+        }
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut decoders = Decoders::new();
+        decoders.register(Std(Gzip), Strip(b'g', log.clone()))
+                .register(Std(Chunked), Strip(b'c', log.clone()))
+                .register(Other("custom"), Strip(b'x', log.clone()));
+
+        assert!(decoders.get(Std(Gzip)).is_some());
+        assert!(decoders.get(Other("CUSTOM")).is_some());
+        assert!(decoders.get(Std(Deflate)).is_none());
+
+        fn is_send_sync<T: Send + Sync>(_: &T) {}
+        is_send_sync(&decoders);
+
+        let body = decoders.decode("custom, gzip, chunked", b"cgxbody").unwrap();
+        assert_eq!(body, b"body");
+        assert_eq!(*log.lock().unwrap(), b"cgx");
+
+        assert_eq!(decoders.decode("identity, gzip, chunked,", b"cgbody").unwrap(), b"body");
+
+        match decoders.decode("gzip, chunked", b"gcbody") {
+            Err(DecodeError::Malformed) => {},
+            _ => panic!(),
+        }
+
+        match decoders.decode("deflate, chunked", b"cbody") {
+            Err(DecodeError::Unsupported(enc)) => {
+                assert_eq!(enc, TransferEncodingBuf::Std(Deflate))
+            },
+            _ => panic!(),
+        }
+
+        decoders.register(Std(Gzip), |input: &[u8]| Ok(input.to_vec()));
+        assert_eq!(decoders.decode("gzip", b"body").unwrap(), b"body");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_chunked() {
+        let dec = ChunkedDecoder::new(16);
+
+        assert_eq!(dec.decode(b"0\r\n\r\n").unwrap(), b"");
+        assert_eq!(dec.decode(b"5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n").unwrap(),
+                   b"hello world");
+        assert_eq!(dec.decode(b"A ;ext=1\r\n0123456789\r\n000\r\nX-Sum: 1\r\n\r\n").unwrap(),
+                   b"0123456789");
+
+        for input in &[&b""[..], b"5\r\nhello", b"5\r\nhello\r\n", b"5\r\nhell\r\n0\r\n\r\n",
+                       b"x\r\n\r\n", b";\r\n\r\n", b"0\r\n\r\nextra",
+                       b"fffffffffffffffffffff\r\n"]
+        {
+            match dec.decode(input) {
+                Err(DecodeError::Malformed) => {},
+                _ => panic!(),
+            }
+        }
+
+        match dec.decode(b"9\r\n012345678\r\n8\r\n01234567\r\n0\r\n\r\n") {
+            Err(DecodeError::TooLarge) => {},
+            _ => panic!(),
+        }
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn test_flate2() {
+        use std::io::Write;
+        use flate2::Compression;
+        use flate2::write::{GzEncoder, ZlibEncoder};
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let payload = b"hello hello hello world";
+
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(payload).unwrap();
+        let gzip = enc.finish().unwrap();
+
+        assert_eq!(decode_all("gzip", &gzip, 1024).unwrap(), payload);
+        assert_eq!(decode_all(" X-GZIP ", &gzip, 1024).unwrap(), payload);
+
+        let mut enc = ZlibEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(&gzip).unwrap();
+        let both = enc.finish().unwrap();
+
+        assert_eq!(decode_all("gzip, deflate", &both, 1024).unwrap(), payload);
+        assert_eq!(DeflateDecoder::new(1024).decode(&both).unwrap(), gzip);
+
+        match decode_all("deflate, gzip", &both, 1024) {
+            Err(DecodeError::Failed(_)) => {},
+            _ => panic!(),
+        }
+
+        match decode_all("gzip", &gzip, payload.len() - 1) {
+            Err(DecodeError::TooLarge) => {},
+            _ => panic!(),
+        }
+
+        assert_eq!(decode_all("gzip", &gzip, payload.len()).unwrap(), payload);
+
+        let mut chunked = b"5\r\n".to_vec();
+        chunked.extend_from_slice(&gzip[..5]);
+        chunked.extend_from_slice(format!("\r\n{:x}\r\n", gzip.len() - 5).as_bytes());
+        chunked.extend_from_slice(&gzip[5..]);
+        chunked.extend_from_slice(b"\r\n0\r\n\r\n");
+        assert_eq!(decode_all("gzip, chunked", &chunked, 1024).unwrap(), payload);
+
+        match decode_all("br, chunked", &chunked, 1024) {
+            Err(DecodeError::Unsupported(enc)) => {
+                assert_eq!(enc, TransferEncodingBuf::Std(Brotli))
+            },
+            _ => panic!(),
+        }
+
+        let mut decoders = Decoders::builtin(1024);
+        decoders.register(Std(Chunked), |input: &[u8]| Ok(input.to_vec()));
+        assert_eq!(decoders.decode("gzip, chunked", &gzip).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_matches() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert!(Other("custom-enc").matches("custom-enc"));
+        assert!(Other("custom-enc").matches("CUSTOM-Enc"));
+        assert!(Other("Custom-Enc").matches(" custom-enc\t"));
+        assert!(!Other("custom-enc").matches("custom"));
+        assert!(!Other("custom-enc").matches("custom enc"));
+        assert!(Std(Gzip).matches("gzip"));
+        assert!(Std(Gzip).matches("GZIP "));
+        assert!(Std(Brotli).matches("br"));
+        assert!(!Std(Brotli).matches("brotli"));
+        assert!(!Std(Gzip).matches("x-gzip"));
+
+        let registry = ["my-enc", "Other-Enc"];
+        let enc = TransferEncoding::new(" OTHER-enc ");
+        assert!(registry.iter().any(|&name| enc.matches(name)));
+    }
+
+    #[test]
+    fn test_ord() {
+        use std::collections::BTreeSet;
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let mut all = StdTransferEncoding::all().to_vec();
+        all.sort();
+        assert_eq!(all, &[Brotli, Chunked, Compress, Deflate, Gzip, Identity, Zstd]);
+
+        let mut encs = vec![Other("b-enc"), Std(Gzip), Other("A-enc"), Std(Chunked),
+                            Other("a-enc2"), Std(Brotli), Other("")];
+        encs.sort();
+        assert_eq!(encs, &[Std(Brotli), Std(Chunked), Std(Gzip), Other(""), Other("A-enc"),
+                           Other("a-enc2"), Other("b-enc")]);
+
+        assert!(Other("ABC") <= Other("abc") && Other("ABC") >= Other("abc"));
+        assert!(Other("Zeta") > Other("alpha"));
+
+        let set = transfer_encodings("custom, gzip, CUSTOM, chunked")
+                      .collect::<BTreeSet<_>>();
+        assert!(set.into_iter().eq(vec![Std(Chunked), Std(Gzip), Other("custom")]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_incremental() {
+        use self::StdTransferEncoding::*;
+
+        let mut p = IncrementalParser::new();
+        assert_eq!(p.feed(b"gz").unwrap().count(), 0);
+        assert_eq!(p.feed(b"ip, chun").unwrap().collect::<Vec<_>>(),
+                   &[TransferEncodingBuf::Std(Gzip)]);
+        assert_eq!(p.feed(b"ked").unwrap().count(), 0);
+        assert_eq!(p.finish(), TransferEncodingBuf::Std(Chunked));
+
+        let mut p = IncrementalParser::new();
+        assert_eq!(p.feed(b"Custom").unwrap().count(), 0);
+        assert!(p.feed(b"\xff,").is_err());
+        assert_eq!(p.feed(b"-enc,,br,").unwrap().collect::<Vec<_>>(),
+                   &[TransferEncodingBuf::Other("Custom-enc".into()),
+                     TransferEncodingBuf::Other("".into()),
+                     TransferEncodingBuf::Std(Brotli)]);
+        assert_eq!(p.feed(b"").unwrap().count(), 0);
+        assert_eq!(p.finish(), TransferEncodingBuf::Other("".into()));
+
+        let s = b" deflate , x-gzip,custom ,  chunked";
+
+        for split in 0..s.len() {
+            let mut p = IncrementalParser::new();
+            let mut encs = p.feed(&s[..split]).unwrap().collect::<Vec<_>>();
+            encs.extend(p.feed(&s[split..]).unwrap());
+            encs.push(p.finish());
+
+            let expected = transfer_encodings_applied(" deflate , x-gzip,custom ,  chunked")
+                .map(|e| e.to_owned())
+                .collect::<Vec<_>>();
+
+            assert_eq!(encs, expected);
         }
     }
-}
 
-/// Standard transfer encoding scheme, as defined by
-/// [IANA](http://www.iana.org/assignments/http-parameters/http-parameters.xhtml#transfer-coding).
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
-pub enum StdTransferEncoding {
-    /// Split into a series of chunks.
-    Chunked,
-    /// UNIX "compress" data format.
-    Compress,
-    /// Deflate compressed data format.
-    Deflate,
-    /// Gzip compressed data format.
-    Gzip,
-}
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert_eq!(Other("GZip-ext"), Other("gzip-ext"));
+        assert_ne!(Other("gzip-ext"), Other("gzip"));
+        assert_ne!(Other("gzip"), Std(Gzip));
+
+        let mut set = HashSet::new();
+        set.insert(Other("GZip-ext"));
+        set.insert(Other("gzip-ext"));
+        assert_eq!(set.len(), 1);
 
-impl std::str::FromStr for StdTransferEncoding {
-    type Err = ();
+        set.insert(Other("gzip"));
+        set.insert(Std(Gzip));
+        set.insert(Std(Gzip));
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&Other("GZIP-EXT")));
+    }
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use self::TransferEncoding::*;
         use self::StdTransferEncoding::*;
 
-        // Names are case-insensitive [RFC7230§4].
-        if s.eq_ignore_ascii_case("chunked") {
-            Ok(Chunked)
-        } else if s.eq_ignore_ascii_case("compress") {
-            Ok(Compress)
-        } else if s.eq_ignore_ascii_case("deflate") {
-            Ok(Deflate)
-        } else if s.eq_ignore_ascii_case("gzip") {
-            Ok(Gzip)
-        } else {
-            Err(())
+        assert_eq!(serde_json::to_string(&Gzip).unwrap(), "\"gzip\"");
+        assert_eq!(serde_json::to_string(&Brotli).unwrap(), "\"br\"");
+        assert_eq!(serde_json::from_str::<StdTransferEncoding>("\"GZip\"").unwrap(), Gzip);
+        assert!(serde_json::from_str::<StdTransferEncoding>("\"custom\"").is_err());
+
+        assert_eq!(serde_json::to_string(&Std(Chunked)).unwrap(), "\"chunked\"");
+        assert_eq!(serde_json::to_string(&Other("custom-enc")).unwrap(), "\"custom-enc\"");
+        assert_eq!(serde_json::from_str::<TransferEncoding>("\"chunked\"").unwrap(),
+                   Std(Chunked));
+        assert_eq!(serde_json::from_str::<TransferEncoding>("\"custom-enc\"").unwrap(),
+                   Other("custom-enc"));
+
+        for enc in &[Std(Zstd), Std(Identity), Other("custom-enc")] {
+            let json = serde_json::to_string(enc).unwrap();
+            assert_eq!(serde_json::from_str::<TransferEncoding>(&json).unwrap(), *enc);
         }
-    }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+        let encs = transfer_encodings("gzip, custom, chunked").collect::<Vec<_>>();
+        let json = serde_json::to_string(&encs).unwrap();
+        assert_eq!(json, "[\"chunked\",\"custom\",\"gzip\"]");
+        assert_eq!(serde_json::from_str::<Vec<TransferEncoding>>(&json).unwrap(), encs);
+
+        assert_eq!(serde_json::from_str::<TransferEncoding>("\"gz\\u0069p\"").unwrap(),
+                   Std(Gzip));
+        assert!(serde_json::from_str::<TransferEncoding>("\"cust\\u006fm\"").is_err());
+    }
 
+    #[cfg(all(feature = "serde", feature = "std"))]
     #[test]
-    fn test_te() {
-        use self::TransferEncoding::*;
+    fn test_serde_owned() {
         use self::StdTransferEncoding::*;
 
-        assert_eq!(TransferEncoding::new("chunked"), Std(Chunked));
-        assert_eq!(TransferEncoding::new("chUNked"), Std(Chunked));
-        assert_eq!(TransferEncoding::new("chun  ked"), Other("chun  ked"));
-        assert_eq!(TransferEncoding::new("  chun\tked\t"), Other("chun\tked"));
-        assert_eq!(TransferEncoding::new("CHUNKED"), Std(Chunked));
-        assert_eq!(TransferEncoding::new("  CHUNKed "), Std(Chunked));
-        assert_eq!(TransferEncoding::new("compress"), Std(Compress));
-        assert_eq!(TransferEncoding::new(" cOMPress  "), Std(Compress));
-        assert_eq!(TransferEncoding::new("deflate"), Std(Deflate));
-        assert_eq!(TransferEncoding::new("\n\r dEflAte \r"), Std(Deflate));
-        assert_eq!(TransferEncoding::new("gzip"), Std(Gzip));
-        assert_eq!(TransferEncoding::new(" GZiP\r\r\t"), Std(Gzip));
-        assert_eq!(TransferEncoding::new("\tgzIP  "), Std(Gzip));
-        assert_eq!(TransferEncoding::new(""), Other(""));
-        assert_eq!(TransferEncoding::new("    \t "), Other(""));
-        assert_eq!(TransferEncoding::new("ÆØБД❤"), Other("ÆØБД❤"));
+        assert_eq!(serde_json::to_string(&TransferEncodingBuf::Std(Gzip)).unwrap(),
+                   "\"gzip\"");
+        assert_eq!(serde_json::from_str::<TransferEncodingBuf>("\"gz\\u0069p\"").unwrap(),
+                   TransferEncodingBuf::Std(Gzip));
+        assert_eq!(serde_json::from_str::<TransferEncodingBuf>("\"cust\\u006fm\"").unwrap(),
+                   TransferEncodingBuf::Other("custom".into()));
     }
 
     #[test]
@@ -141,5 +2140,561 @@ mod test {
         assert_eq!(te.next().unwrap(), Other("hello"));
         assert_eq!(te.next().unwrap(), Std(Deflate));
         assert!(te.next().is_none());
+
+        let mut te = transfer_encodings("br, chunked");
+        assert_eq!(te.next().unwrap(), Std(Chunked));
+        assert_eq!(te.next().unwrap(), Std(Brotli));
+        assert!(te.next().is_none());
+    }
+
+    #[test]
+    fn test_validate_layers() {
+        assert_eq!(validate_layers("gzip, chunked"), Ok(()));
+        assert_eq!(validate_layers("chunked"), Ok(()));
+        assert_eq!(validate_layers("gzip, deflate"), Ok(()));
+        assert_eq!(validate_layers("custom, GZIP,  CHUNKED "), Ok(()));
+        assert_eq!(validate_layers(""), Ok(()));
+        assert_eq!(validate_layers("gzip, chunked,"), Ok(()));
+        assert_eq!(validate_layers("gzip, chunked, ,"), Ok(()));
+        assert_eq!(validate_layers("chunked, gzip"),
+                   Err(TransferEncodingError::ChunkedNotLast));
+        assert_eq!(validate_layers("gzip, chunked, custom"),
+                   Err(TransferEncodingError::ChunkedNotLast));
+        assert_eq!(validate_layers("chunked, chunked"),
+                   Err(TransferEncodingError::DuplicateChunked));
+        assert_eq!(validate_layers("chunked, gzip, Chunked"),
+                   Err(TransferEncodingError::DuplicateChunked));
+        assert_eq!(validate_layers("chunked, Chunked, gzip"),
+                   Err(TransferEncodingError::DuplicateChunked));
+    }
+
+    #[test]
+    fn test_ends_with_chunked() {
+        assert!(ends_with_chunked("gzip, chunked"));
+        assert!(ends_with_chunked("chunked"));
+        assert!(ends_with_chunked(" CHUNKED "));
+        assert!(ends_with_chunked("gzip, chunked, "));
+        assert!(!ends_with_chunked("chunked, gzip"));
+        assert!(!ends_with_chunked("gzip"));
+        assert!(!ends_with_chunked("chunked-ext"));
+        assert!(!ends_with_chunked(""));
+        assert!(!ends_with_chunked(" , "));
+    }
+
+    #[test]
+    fn test_duplicates() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert!(has_duplicate_chunked("chunked, chunked"));
+        assert!(has_duplicate_chunked("chunked, gzip, CHUNKED"));
+        assert!(!has_duplicate_chunked("gzip, chunked"));
+        assert!(!has_duplicate_chunked("gzip, gzip"));
+
+        let mut d = duplicate_encodings("chunked, chunked");
+        assert_eq!(d.next(), Some(Std(Chunked)));
+        assert_eq!(d.next(), None);
+
+        let mut d = duplicate_encodings("gzip, x-gzip, Custom, chunked, GZIP, custom");
+        assert_eq!(d.next(), Some(Other("Custom")));
+        assert_eq!(d.next(), Some(Std(Gzip)));
+        assert_eq!(d.next(), Some(Std(Gzip)));
+        assert_eq!(d.next(), None);
+
+        assert_eq!(duplicate_encodings("gzip, custom, chunked").next(), None);
+        assert_eq!(duplicate_encodings("gzip,, ,chunked,").next(), None);
+        assert_eq!(duplicate_encodings("").next(), None);
+        assert_eq!(duplicate_encodings("custom;a=1, custom2, CUSTOM").next(),
+                   Some(Other("custom")));
+
+        let s = "chunked, gzip, chunked";
+        assert!(has_duplicate_chunked(s));
+        assert_eq!(duplicate_encodings(s).collect::<Vec<_>>(), &[Std(Chunked)]);
+    }
+
+    #[test]
+    fn test_params() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert_eq!(TransferEncoding::new("gzip;foo=bar"), Std(Gzip));
+        assert_eq!(TransferEncoding::new(" custom ; foo=bar"), Other("custom"));
+
+        let c = TransferCoding::new("gzip;q=1.0");
+        assert_eq!(c.encoding, Std(Gzip));
+        let mut p = c.params();
+        assert_eq!(p.next(), Some(("q", Some("1.0"))));
+        assert_eq!(p.next(), None);
+
+        let c = TransferCoding::new("custom; a=\"x;y\"; b=2");
+        assert_eq!(c.encoding, Other("custom"));
+        let mut p = c.params();
+        assert_eq!(p.next(), Some(("a", Some("x;y"))));
+        assert_eq!(p.next(), Some(("b", Some("2"))));
+        assert_eq!(p.next(), None);
+
+        let c = TransferCoding::new("custom;a=\"x\\\";y\";;flag");
+        let mut p = c.params();
+        assert_eq!(p.next(), Some(("a", Some("x\\\";y"))));
+        assert_eq!(p.next(), Some(("flag", None)));
+        assert_eq!(p.next(), None);
+
+        let c = TransferCoding::new("chunked");
+        assert_eq!(c.encoding, Std(Chunked));
+        assert_eq!(c.params().next(), None);
+
+        let mut tc = transfer_codings("gzip;q=0.5, chunked");
+        let c = tc.next().unwrap();
+        assert_eq!(c.encoding, Std(Chunked));
+        assert_eq!(c.params().next(), None);
+        let c = tc.next().unwrap();
+        assert_eq!(c.encoding, Std(Gzip));
+        assert_eq!(c.params().collect::<Vec<_>>(), &[("q", Some("0.5"))]);
+        assert!(tc.next().is_none());
+    }
+
+    #[test]
+    fn test_te_codings() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let mut te = te_codings("trailers, deflate;q=0.5");
+        assert_eq!(te.next(), Some(TeCoding::Trailers));
+        let c = te.next().unwrap();
+        assert_eq!(c.q(), Some("0.5"));
+        match c {
+            TeCoding::Coding(c) => assert_eq!(c.encoding, Std(Deflate)),
+            TeCoding::Trailers => panic!(),
+        }
+        assert_eq!(te.next(), None);
+
+        let mut te = te_codings(" TRAILERS ,gzip, custom;Q=0");
+        assert_eq!(te.next(), Some(TeCoding::Trailers));
+        assert_eq!(te.next(), Some(TeCoding::Coding(TransferCoding::new("gzip"))));
+        let c = te.next().unwrap();
+        assert_eq!(c.q(), Some("0"));
+        assert_eq!(c, TeCoding::Coding(TransferCoding::new("custom;Q=0")));
+        assert_eq!(te.next(), None);
+
+        assert_eq!(TeCoding::Trailers.q(), None);
+        assert_eq!(TeCoding::new("gzip").q(), None);
+        assert!(te_codings("gzip, chunked").all(|c| c != TeCoding::Trailers));
+
+        let c = TransferCoding::new("custom; a=1; B=2; b=3");
+        assert_eq!(c.encoding, Other("custom"));
+        assert_eq!(c.param("a"), Some("1"));
+        assert_eq!(c.param("b"), Some("2"));
+        assert_eq!(c.param("c"), None);
+    }
+
+    #[test]
+    fn test_quality() {
+        assert_eq!(TransferCoding::new("gzip;q=0").quality(), Ok(Some(0.0)));
+        assert_eq!(TransferCoding::new("gzip;q=0.750").quality(), Ok(Some(0.75)));
+        assert_eq!(TransferCoding::new("gzip;q=0.").quality(), Ok(Some(0.0)));
+        assert_eq!(TransferCoding::new("gzip;q=0.001").quality(), Ok(Some(0.001)));
+        assert_eq!(TransferCoding::new("gzip;q=0.5").quality(), Ok(Some(0.5)));
+        assert_eq!(TransferCoding::new("gzip;q=1").quality(), Ok(Some(1.0)));
+        assert_eq!(TransferCoding::new("gzip;Q=1.000").quality(), Ok(Some(1.0)));
+        assert_eq!(TransferCoding::new("gzip").quality(), Ok(None));
+        assert_eq!(TransferCoding::new("gzip;a=b").quality(), Ok(None));
+
+        for q in &["9", "2", "1.5", "1.001", "0.7501", "-0", ".5", "", "0.a", "00.5",
+                   "1.0000"]
+        {
+            assert_eq!(TransferCoding::new(&format!("gzip;q={}", q)).quality(),
+                       Err(ParseEncodingError::InvalidQuality));
+        }
+
+        let mut te = te_codings("trailers, deflate;q=0.5, gzip;q=9");
+        assert_eq!(te.next().unwrap().quality(), Ok(None));
+        assert_eq!(te.next().unwrap().quality(), Ok(Some(0.5)));
+        assert_eq!(te.next().unwrap().quality(), Err(ParseEncodingError::InvalidQuality));
+    }
+
+    #[test]
+    fn test_params_ows() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let expected = [("q", Some("1")), ("a", Some("x ; y"))];
+
+        for s in &["gzip;q=1;a=\"x ; y\"", "gzip ; q=1 ; a=\"x ; y\"",
+                   "gzip\t;\tq\t=\t1\t;\ta\t=\t\"x ; y\"\t",
+                   "gzip ;q = 1;  a =  \"x ; y\"  "]
+        {
+            let c = TransferCoding::new(s);
+            assert_eq!(c.encoding, Std(Gzip));
+            assert_eq!(c.params().collect::<Vec<_>>(), expected);
+            assert_eq!(c.quality(), Ok(Some(1.0)));
+        }
+
+        let c = TransferCoding::new("custom ; flag ; b = ");
+        assert_eq!(c.encoding, Other("custom"));
+        assert_eq!(c.params().collect::<Vec<_>>(), &[("flag", None), ("b", Some(""))]);
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert_eq!(TransferEncoding::parse_strict("chunked"), Ok(Std(Chunked)));
+        assert_eq!(TransferEncoding::parse_strict("  GZIP\t"), Ok(Std(Gzip)));
+        assert_eq!(TransferEncoding::parse_strict("custom-enc"), Ok(Other("custom-enc")));
+        assert_eq!(TransferEncoding::parse_strict("x_token"), Ok(Other("x_token")));
+        assert_eq!(TransferEncoding::parse_strict(" a!#$%&'*+-.^_`|~9 "),
+                   Ok(Other("a!#$%&'*+-.^_`|~9")));
+        assert_eq!(TransferEncoding::parse_strict("chun ked"),
+                   Err(ParseEncodingError::InvalidToken));
+        assert_eq!(TransferEncoding::parse_strict("a/b"), Err(ParseEncodingError::InvalidToken));
+        assert_eq!(TransferEncoding::parse_strict("a\tb"),
+                   Err(ParseEncodingError::InvalidToken));
+        assert_eq!(TransferEncoding::parse_strict("a\x01b"),
+                   Err(ParseEncodingError::InvalidToken));
+        assert_eq!(TransferEncoding::parse_strict("\"a\""),
+                   Err(ParseEncodingError::InvalidToken));
+        assert_eq!(TransferEncoding::parse_strict("ÆØ"), Err(ParseEncodingError::InvalidToken));
+        assert_eq!(TransferEncoding::parse_strict(""), Err(ParseEncodingError::Empty));
+        assert_eq!(TransferEncoding::parse_strict("  "), Err(ParseEncodingError::Empty));
+
+        for s in &["\u{a0}chunked", "chunked\u{a0}", "\x0bchunked", "\x0cchunked",
+                   "chunked\r\n", "\r\nchunked", "\u{2028}chunked", "\u{a0}"]
+        {
+            assert_eq!(TransferEncoding::parse_strict(s), Err(ParseEncodingError::InvalidToken));
+        }
+
+        assert_eq!(TransferEncoding::parse_strict("gzip;q=1"), Ok(Std(Gzip)));
+        assert_eq!(TransferEncoding::parse_strict("custom ; a = b ;c=\"x;\\\"y\"\t"),
+                   Ok(Other("custom")));
+        assert_eq!(TransferEncoding::parse_strict("custom;a=\"\""), Ok(Other("custom")));
+
+        for s in &["chunked; \u{1}bad", "chunked;", "chunked;;a=b", "chunked;flag",
+                   "chunked;a=", "chunked;a b=c", "chunked;a=b c", "chunked;a=\"b",
+                   "chunked;a=\"b\\\"", "chunked;a=\"\u{1}\"", "chunked;a=b\"c\""]
+        {
+            assert_eq!(TransferEncoding::parse_strict(s), Err(ParseEncodingError::InvalidParam));
+        }
+
+        let mut encs = transfer_encodings_strict("gzip, \u{a0}chunked");
+        assert_eq!(encs.next(), Some(Err(ParseEncodingError::InvalidToken)));
+        assert_eq!(encs.next(), Some(Ok(Std(Gzip))));
+    }
+
+    #[test]
+    fn test_bytes() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert_eq!(TransferEncoding::from_bytes(b"chunked"), Ok(Std(Chunked)));
+        assert_eq!(TransferEncoding::from_bytes(b" GZip\t"), Ok(Std(Gzip)));
+        assert_eq!(TransferEncoding::from_bytes(b" custom-enc "), Ok(Other("custom-enc")));
+        assert_eq!(TransferEncoding::from_bytes(b"\xff"), Err(ParseEncodingError::NonAscii));
+        assert_eq!(TransferEncoding::from_bytes("ÆØ".as_bytes()),
+                   Err(ParseEncodingError::NonAscii));
+
+        let mut te = transfer_encodings_bytes(b"custom-enc, chunked").unwrap();
+        assert_eq!(te.next().unwrap(), Std(Chunked));
+        assert_eq!(te.next().unwrap(), Other("custom-enc"));
+        assert!(te.next().is_none());
+
+        assert!(transfer_encodings_bytes(b"gzip, cust\x80om, chunked").is_err());
+    }
+
+    #[test]
+    fn test_tes_clone() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let mut te = transfer_encodings("gzip, custom, br, chunked");
+        assert_eq!(te.next().unwrap(), Std(Chunked));
+
+        let mut te2 = te.clone();
+        assert_eq!(te.next().unwrap(), Std(Brotli));
+        assert_eq!(te.next().unwrap(), Other("custom"));
+        assert_eq!(te.next().unwrap(), Std(Gzip));
+        assert!(te.next().is_none());
+
+        assert_eq!(te2.next().unwrap(), Std(Brotli));
+        assert_eq!(te2.collect::<Vec<_>>(), &[Other("custom"), Std(Gzip)]);
+    }
+
+    #[test]
+    fn test_count_layers() {
+        assert_eq!(count_layers("gzip, chunked"), 2);
+        assert_eq!(count_layers("gzip, , chunked"), 3);
+        assert_eq!(count_layers("chunked,"), 2);
+        assert_eq!(count_layers("chunked"), 1);
+        assert_eq!(count_layers(""), 1);
+
+        let mut te = transfer_encodings("gzip, , chunked");
+        assert_eq!(te.len(), 3);
+        te.next();
+        assert_eq!(te.len(), 2);
+        te.next_back();
+        assert_eq!(te.len(), 1);
+        te.next();
+        assert_eq!(te.len(), 0);
+        assert!(te.next().is_none());
+        assert!(te.next_back().is_none());
+        assert_eq!(te.len(), 0);
+
+        assert_eq!(transfer_encodings_applied("gzip, deflate, chunked").len(), 3);
+    }
+
+    #[test]
+    fn test_filter() {
+        use self::StdTransferEncoding::*;
+
+        let mut te = transfer_encodings("gzip, custom, chunked").std_only();
+        assert_eq!(te.next(), Some(Chunked));
+        assert_eq!(te.next(), Some(Gzip));
+        assert_eq!(te.next(), None);
+
+        let mut te = transfer_encodings("gzip, custom, chunked, other").other_only();
+        assert_eq!(te.next(), Some("other"));
+        assert_eq!(te.next(), Some("custom"));
+        assert_eq!(te.next(), None);
+
+        let mut te = transfer_encodings("gzip, custom, br, chunked").std_only();
+        assert_eq!(te.next_back(), Some(Gzip));
+        assert_eq!(te.next(), Some(Chunked));
+        assert_eq!(te.next_back(), Some(Brotli));
+        assert_eq!(te.next(), None);
+
+        assert_eq!(transfer_encodings("gzip, deflate").other_only().next(), None);
+        assert!(transfer_encodings("a, b, c").std_only().rev().eq(None));
+    }
+
+    #[test]
+    fn test_tes_strict() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let mut te = transfer_encodings_strict("gzip, custom, chunked");
+        assert_eq!(te.next(), Some(Ok(Std(Chunked))));
+        assert_eq!(te.next(), Some(Ok(Other("custom"))));
+        assert_eq!(te.next(), Some(Ok(Std(Gzip))));
+        assert_eq!(te.next(), None);
+
+        let mut te = transfer_encodings_strict("gzip,,chunked");
+        assert_eq!(te.next(), Some(Ok(Std(Chunked))));
+        assert_eq!(te.next(), Some(Err(ParseEncodingError::Empty)));
+        assert_eq!(te.next(), Some(Ok(Std(Gzip))));
+        assert_eq!(te.next(), None);
+
+        let mut te = transfer_encodings_strict("gzip, chunked, ");
+        assert_eq!(te.next(), Some(Err(ParseEncodingError::Empty)));
+        assert_eq!(te.next(), Some(Ok(Std(Chunked))));
+        assert_eq!(te.next_back(), Some(Ok(Std(Gzip))));
+        assert_eq!(te.next(), None);
+
+        let mut te = transfer_encodings_strict("a b, chunked");
+        assert_eq!(te.next(), Some(Ok(Std(Chunked))));
+        assert_eq!(te.next(), Some(Err(ParseEncodingError::InvalidToken)));
+        assert_eq!(te.next(), None);
+
+        assert!(transfer_encodings_strict("gzip,,chunked").collect::<Result<Vec<_>, _>>()
+                    .is_err());
+    }
+
+    #[test]
+    fn test_tes_multi() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        assert!(transfer_encodings_multi(vec!["gzip", "chunked"])
+                    .eq(transfer_encodings("gzip, chunked")));
+        let lines = ["deflate, custom", " br", "gzip ,chunked"];
+        assert!(transfer_encodings_multi(lines.iter().cloned())
+                    .eq(transfer_encodings("deflate, custom, br, gzip, chunked")));
+
+        let mut te = transfer_encodings_multi(vec!["gzip, x", "chunked"]);
+        assert_eq!(te.next(), Some(Std(Chunked)));
+        assert_eq!(te.next(), Some(Other("x")));
+        assert_eq!(te.next(), Some(Std(Gzip)));
+        assert_eq!(te.next(), None);
+        assert_eq!(te.next(), None);
+
+        assert_eq!(transfer_encodings_multi(Vec::<&str>::new()).next(), None);
+    }
+
+    #[test]
+    fn test_tes_limited() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let mut te = transfer_encodings_limited("gzip, custom, chunked", 3);
+        assert_eq!(te.next(), Some(Ok(Std(Chunked))));
+        assert_eq!(te.next(), Some(Ok(Other("custom"))));
+        assert_eq!(te.next(), Some(Ok(Std(Gzip))));
+        assert_eq!(te.next(), None);
+
+        let mut te = transfer_encodings_limited("deflate, gzip, custom, chunked", 3);
+        assert_eq!(te.next(), Some(Ok(Std(Chunked))));
+        assert_eq!(te.next(), Some(Ok(Other("custom"))));
+        assert_eq!(te.next(), Some(Ok(Std(Gzip))));
+        assert_eq!(te.next(), Some(Err(TransferEncodingError::TooManyLayers)));
+        assert_eq!(te.next(), None);
+
+        let mut te = transfer_encodings_limited("chunked", 0);
+        assert_eq!(te.next(), Some(Err(TransferEncodingError::TooManyLayers)));
+        assert_eq!(te.next(), None);
+
+        let long = vec!["gzip"; 1000].join(",");
+        assert!(transfer_encodings_limited(&long, 1000).all(|e| e.is_ok()));
+        assert!(transfer_encodings_limited(&long, 999).any(|e| e.is_err()));
+    }
+
+    #[test]
+    fn test_effective() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let mut te = effective_encodings("");
+        assert_eq!(te.next(), Some(Std(Identity)));
+        assert_eq!(te.next(), None);
+
+        let mut te = effective_encodings("   \t");
+        assert_eq!(te.next(), Some(Std(Identity)));
+        assert_eq!(te.next(), None);
+
+        assert!(effective_encodings("gzip, chunked").eq(transfer_encodings("gzip, chunked")));
+        assert!(effective_encodings(" , ").eq(transfer_encodings(" , ")));
+    }
+
+    #[test]
+    fn test_header() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let header = TransferEncodingHeader::new("gzip, custom, chunked");
+        assert_eq!(header.as_str(), "gzip, custom, chunked");
+
+        let mut encs = Vec::new();
+
+        for enc in header {
+            encs.push(enc);
+        }
+
+        assert_eq!(encs, &[Std(Chunked), Other("custom"), Std(Gzip)]);
+        assert!(header.into_iter().eq(encs));
+
+        let mut te = header.into_iter();
+        assert_eq!(te.next(), Some(Std(Chunked)));
+        assert_eq!(te.next_back(), Some(Std(Gzip)));
+    }
+
+    #[test]
+    fn test_tes_spans() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let mut te = transfer_encodings_spans(" GZip ,Custom-Enc;q=1,\tCHUNKED\r");
+        assert_eq!(te.next(), Some((Std(Chunked), "\tCHUNKED\r")));
+        assert_eq!(te.next(), Some((Other("custom-enc"), "Custom-Enc;q=1")));
+        assert_eq!(te.next(), Some((Std(Gzip), " GZip ")));
+        assert_eq!(te.next(), None);
+
+        let mut te = transfer_encodings_spans("gzip,, ");
+        assert_eq!(te.next(), Some((Other(""), " ")));
+        assert_eq!(te.next_back(), Some((Std(Gzip), "gzip")));
+        assert_eq!(te.next(), Some((Other(""), "")));
+        assert_eq!(te.next(), None);
+
+        let s = "deflate, x, chunked";
+        assert!(transfer_encodings_spans(s).map(|(e, _)| e).eq(transfer_encodings(s)));
+    }
+
+    #[test]
+    fn test_tes_indexed() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let s = "gzip, chunked";
+        let mut te = transfer_encodings_indexed(s);
+        assert_eq!(te.next(), Some((Std(Chunked), 6..13)));
+        assert_eq!(te.next(), Some((Std(Gzip), 0..4)));
+        assert_eq!(te.next(), None);
+
+        for (enc, range) in transfer_encodings_indexed(s) {
+            assert!(enc.matches(&s[range]));
+        }
+
+        let s = " X-GZip ,\tCustom ; q=1,, ";
+        let mut te = transfer_encodings_indexed(s);
+        let (enc, range) = te.next().unwrap();
+        assert_eq!((enc, &s[range]), (Other(""), ""));
+        let (enc, range) = te.next().unwrap();
+        assert_eq!((enc, &s[range]), (Other(""), ""));
+        let (enc, range) = te.next().unwrap();
+        assert_eq!((enc, &s[range.clone()]), (Other("custom"), "Custom"));
+        assert_eq!(range, 10..16);
+        let (enc, range) = te.next_back().unwrap();
+        assert_eq!((enc, &s[range.clone()]), (Std(Gzip), "X-GZip"));
+        assert_eq!(range, 1..7);
+        assert_eq!(te.next(), None);
+    }
+
+    #[test]
+    fn test_collect_codings() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let (encs, len) = collect_codings::<4>("gzip, custom, chunked").unwrap();
+        assert_eq!(len, 3);
+        assert_eq!(&encs[..len], &[Std(Chunked), Other("custom"), Std(Gzip)]);
+        assert_eq!(encs[3], Other(""));
+
+        let (encs, len) = collect_codings::<2>("gzip, chunked").unwrap();
+        assert_eq!(len, 2);
+        assert_eq!(encs, [Std(Chunked), Std(Gzip)]);
+
+        assert_eq!(collect_codings::<2>("deflate, gzip, chunked"),
+                   Err(TransferEncodingError::TooManyLayers));
+        assert_eq!(collect_codings::<0>("chunked"), Err(TransferEncodingError::TooManyLayers));
+    }
+
+    #[test]
+    fn test_tes_applied() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let mut te = transfer_encodings_applied("gzip, chunked");
+        assert_eq!(te.next().unwrap(), Std(Gzip));
+        assert_eq!(te.next().unwrap(), Std(Chunked));
+        assert!(te.next().is_none());
+
+        let mut te = transfer_encodings_applied("\tdeflate,hello,   UNknown\r\r");
+        assert_eq!(te.next().unwrap(), Std(Deflate));
+        assert_eq!(te.next().unwrap(), Other("hello"));
+        assert_eq!(te.next().unwrap(), Other("UNknown"));
+        assert!(te.next().is_none());
+    }
+
+    #[test]
+    fn test_tes_rev() {
+        use self::TransferEncoding::*;
+        use self::StdTransferEncoding::*;
+
+        let mut te = transfer_encodings("chunked, gzip, compress");
+        assert_eq!(te.next_back().unwrap(), Std(Chunked));
+        assert_eq!(te.next_back().unwrap(), Std(Gzip));
+        assert_eq!(te.next_back().unwrap(), Std(Compress));
+        assert!(te.next_back().is_none());
+
+        let mut te = transfer_encodings("gzip, custom, br, chunked");
+        assert_eq!(te.next().unwrap(), Std(Chunked));
+        assert_eq!(te.next_back().unwrap(), Std(Gzip));
+        assert_eq!(te.next().unwrap(), Std(Brotli));
+        assert_eq!(te.next_back().unwrap(), Other("custom"));
+        assert!(te.next().is_none());
+        assert!(te.next_back().is_none());
+
+        let s = "  gzip ,deflate,, custom-enc, chunked";
+        let fwd = transfer_encodings(s).collect::<Vec<_>>();
+        let mut back = transfer_encodings(s).rev().collect::<Vec<_>>();
+        back.reverse();
+        assert_eq!(fwd, back);
     }
 }
@@ -0,0 +1,110 @@
+//! Serialization of encodings as their header tokens, enabled by the `serde` feature.
+
+use serde::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use {StdTransferEncoding, TransferEncoding};
+#[cfg(feature = "std")]
+use TransferEncodingBuf;
+
+impl Serialize for StdTransferEncoding {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(self.canonical_name())
+    }
+}
+
+impl<'de> Deserialize<'de> for StdTransferEncoding {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_str(StdVisitor)
+    }
+}
+
+/// Visitor for parsing a standard encoding from any string.
+struct StdVisitor;
+
+impl<'de> Visitor<'de> for StdVisitor {
+    type Value = StdTransferEncoding;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("a standard transfer encoding name")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+impl<'a> Serialize for TransferEncoding<'a> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            TransferEncoding::Std(ref enc) => enc.serialize(s),
+            TransferEncoding::Other(name) => s.serialize_str(name),
+        }
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for TransferEncoding<'a> {
+    /// Deserialize an encoding by borrowing its name from the input, yielding `Other`
+    /// for unknown names.
+    ///
+    /// Standard names are also accepted from transient strings, such as those containing
+    /// escapes, but unknown names must be borrowable. Deserialize a `TransferEncodingBuf`
+    /// to accept any string.
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_str(EncodingVisitor(core::marker::PhantomData))
+    }
+}
+
+/// Visitor for parsing an encoding that borrows from the input.
+struct EncodingVisitor<'a>(core::marker::PhantomData<&'a str>);
+
+impl<'de: 'a, 'a> Visitor<'de> for EncodingVisitor<'a> {
+    type Value = TransferEncoding<'a>;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("a borrowed transfer encoding name")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(TransferEncoding::new(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        match TransferEncoding::new(v) {
+            TransferEncoding::Std(enc) => Ok(TransferEncoding::Std(enc)),
+            TransferEncoding::Other(_) => Err(E::invalid_value(Unexpected::Str(v), &self)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Serialize for TransferEncodingBuf {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.as_encoding().serialize(s)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de> Deserialize<'de> for TransferEncodingBuf {
+    /// Deserialize an encoding from any string, yielding `Other` for unknown names.
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_str(BufVisitor)
+    }
+}
+
+/// Visitor for parsing an owned encoding from any string.
+#[cfg(feature = "std")]
+struct BufVisitor;
+
+#[cfg(feature = "std")]
+impl<'de> Visitor<'de> for BufVisitor {
+    type Value = TransferEncodingBuf;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("a transfer encoding name")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(TransferEncoding::new(v).to_owned())
+    }
+}